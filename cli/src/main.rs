@@ -0,0 +1,376 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use monet::GraphPaper;
+use monet::graph_paper::{XScale, YScale};
+
+const USAGE:&str = "\
+Usage: cli <CSV> --columns X:Y[,X:Y...] --x AXIS --y AXIS [--title TEXT] [--out PATH] [--save-spec PATH]
+       cli --spec PATH [--out PATH]
+
+Plots the given column pairs of a CSV as points and writes the SVG.
+Columns are counted from 0. The first row of the CSV is taken as headers.
+
+Axes:
+  linear:FROM:TO[:GREAT[:SHORT]]  Linear axis. FROM must be 0
+  log:BASE:FROM:TO                Log axis from BASE^FROM to BASE^TO
+  time[:GREAT[:SHORT]]            Time axis spanning the data
+Append :reversed to any axis to flip its direction.
+
+Options:
+  --columns  Column pairs to plot, such as 0:1,0:2
+  --x, --y   The axes
+  --title    The title of the graph
+  --out, -o  Where to write the SVG. Writes to stdout when omitted,
+             unless only --save-spec is asked for
+  --spec     Plots a .monet.toml (or .json) spec instead of a CSV
+  --save-spec
+             Saves the graph as a spec that reads the CSV again
+  --help, -h Print this message
+";
+
+const GREAT_SPLIT:u32 = 10;
+const SHORT_SPLIT:u32 = 5;
+
+#[derive(Debug, PartialEq)]
+enum AxisKind {
+    Linear { max_value: f32, great_split: u32, short_split: u32 },
+    Log { base: f32, from: i32, to: i32 },
+    Time { great_split: u32, short_split: u32 }
+}
+
+#[derive(Debug, PartialEq)]
+struct Axis {
+    kind: AxisKind,
+    reversed: bool
+}
+
+impl Axis {
+    /// Parses specs such as `linear:0:100` and `log:10:-1:2`
+    fn parse(spec:&str) -> Result<Self, String> {
+        let mut fields = spec.split(':').collect::<Vec<&str>>();
+        let reversed = fields.last() == Some(&"reversed");
+        if reversed {
+            fields.pop();
+        }
+        fn number<T:std::str::FromStr>(spec:&str, field:Option<&&str>, name:&str) -> Result<T, String> {
+            let field = field.ok_or(format!("The axis {} lacks its {}.", spec, name))?;
+            field.trim().parse::<T>().map_err(|_| format!("The {} of the axis {} isn't a number: {}", name, spec, field))
+        }
+        fn splits(spec:&str, fields:&[&str]) -> Result<(u32, u32), String> {
+            Ok((
+                fields.first().map_or(Ok(GREAT_SPLIT), |_| number(spec, fields.first(), "great split"))?,
+                fields.get(1).map_or(Ok(SHORT_SPLIT), |_| number(spec, fields.get(1), "short split"))?
+            ))
+        }
+        let kind = match fields.first().copied() {
+            Some("linear") => {
+                if fields.len() > 5 {
+                    return Err(format!("A linear axis takes a start, an end and two splits, but got {}.", spec));
+                }
+                let from = number::<f32>(spec, fields.get(1), "start")?;
+                if from != 0.0 {
+                    return Err(format!("A linear axis starts at 0, but {} starts at {}.", spec, from));
+                }
+                let (great_split, short_split) = splits(spec, fields.get(3..).unwrap_or_default())?;
+                AxisKind::Linear { max_value: number(spec, fields.get(2), "end")?, great_split, short_split }
+            },
+            Some("log") => {
+                if fields.len() > 4 {
+                    return Err(format!("A log axis takes a base, a start and an end, but got {}.", spec));
+                }
+                AxisKind::Log {
+                    base: number(spec, fields.get(1), "base")?,
+                    from: number(spec, fields.get(2), "start exponent")?,
+                    to: number(spec, fields.get(3), "end exponent")?
+                }
+            },
+            Some("time") => {
+                if fields.len() > 3 {
+                    return Err(format!("A time axis takes up to two splits, but got {}.", spec));
+                }
+                let (great_split, short_split) = splits(spec, fields.get(1..).unwrap_or_default())?;
+                AxisKind::Time { great_split, short_split }
+            },
+            _ => return Err(format!("Unknown axis {}. Use linear, log or time.", spec))
+        };
+        Ok(Axis { kind, reversed })
+    }
+
+    fn parse_value(&self, field:&str) -> Option<f64> {
+        match self.kind {
+            AxisKind::Time { .. } => monet::math::calendar::parse_timestamp(field),
+            _ => field.trim().parse::<f64>().ok()
+        }
+    }
+
+    /// The span of a time axis. Other axes keep their origin at zero
+    fn span(&self, values:impl Iterator<Item = f64>) -> (f64, f64) {
+        match self.kind {
+            AxisKind::Time { .. } => monet::spec::time_span(values),
+            _ => (0.0, 0.0)
+        }
+    }
+
+    fn x_scale(&self, span:(f64, f64)) -> Box<dyn XScale> {
+        match self.kind {
+            AxisKind::Linear { max_value, great_split, short_split } => Box::new(monet::XLinearScale {
+                h_great_split: great_split,
+                h_short_split: short_split,
+                max_value,
+                reversed: self.reversed
+            }),
+            AxisKind::Log { base, from, to } => Box::new(monet::XLogScale {
+                base,
+                from,
+                to,
+                tick: 10,
+                reversed: self.reversed
+            }),
+            AxisKind::Time { great_split, short_split } => Box::new(monet::XTimeScale {
+                start: span.0,
+                end: span.1,
+                h_great_split: great_split,
+                h_short_split: short_split,
+                reversed: self.reversed
+            })
+        }
+    }
+
+    fn y_scale(&self, span:(f64, f64)) -> Box<dyn YScale> {
+        match self.kind {
+            AxisKind::Linear { max_value, great_split, short_split } => Box::new(monet::YLinearScale {
+                v_great_split: great_split,
+                v_short_split: short_split,
+                max_value,
+                reversed: self.reversed
+            }),
+            AxisKind::Log { base, from, to } => Box::new(monet::YLogScale {
+                base,
+                from,
+                to,
+                reversed: self.reversed
+            }),
+            AxisKind::Time { great_split, short_split } => Box::new(monet::YTimeScale {
+                start: span.0,
+                end: span.1,
+                v_great_split: great_split,
+                v_short_split: short_split,
+                reversed: self.reversed
+            })
+        }
+    }
+}
+
+struct Plot {
+    csv_path: PathBuf,
+    columns: Vec<(usize, usize)>,
+    x: Axis,
+    y: Axis,
+    title: String,
+    spec_path: Option<PathBuf>
+}
+
+enum Source {
+    Csv(Plot),
+    Spec(PathBuf)
+}
+
+struct Args {
+    source: Source,
+    out_path: Option<PathBuf>
+}
+
+/// Parses `0:1,0:2` into column pairs
+fn parse_columns(spec:&str) -> Result<Vec<(usize, usize)>, String> {
+    spec.split(',')
+        .map(|pair| {
+            let (x, y) = pair.split_once(':').ok_or(format!("The column pair {} isn't in the form X:Y.", pair))?;
+            match (x.trim().parse::<usize>(), y.trim().parse::<usize>()) {
+                (Ok(x), Ok(y)) => Ok((x, y)),
+                _ => Err(format!("The column pair {} isn't made of column numbers.", pair))
+            }
+        })
+        .collect()
+}
+
+/// Returns `None` when help was asked for
+fn parse_args(mut args:impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut csv_path = None;
+    let mut columns = None;
+    let (mut x, mut y) = (None, None);
+    let mut title = None;
+    let mut out_path = None;
+    let (mut spec, mut save_spec) = (None, None);
+    while let Some(arg) = args.next() {
+        let mut value = |name:&str| args.next().ok_or(format!("{} needs a value.", name));
+        match arg.as_str() {
+            "--help" | "-h" => return Ok(None),
+            "--columns" => columns = Some(parse_columns(&value(&arg)?)?),
+            "--x" => x = Some(Axis::parse(&value(&arg)?)?),
+            "--y" => y = Some(Axis::parse(&value(&arg)?)?),
+            "--title" => title = Some(value(&arg)?),
+            "--out" | "-o" => out_path = Some(PathBuf::from(value(&arg)?)),
+            "--spec" => spec = Some(PathBuf::from(value(&arg)?)),
+            "--save-spec" => save_spec = Some(PathBuf::from(value(&arg)?)),
+            _ if arg.starts_with('-') => return Err(format!("Unknown option {}.", arg)),
+            _ if csv_path.is_none() => csv_path = Some(PathBuf::from(arg)),
+            _ => return Err(format!("Only one CSV can be plotted, but {} was also given.", arg))
+        }
+    }
+    if let Some(spec) = spec {
+        // The spec already holds everything else
+        if csv_path.is_some() || columns.is_some() || x.is_some() || y.is_some() || title.is_some() || save_spec.is_some() {
+            return Err("--spec only goes with --out.".to_string());
+        }
+        return Ok(Some(Args { source: Source::Spec(spec), out_path }));
+    }
+    Ok(Some(Args {
+        source: Source::Csv(Plot {
+            csv_path: csv_path.ok_or("The CSV wasn't specified.")?,
+            columns: columns.ok_or("--columns wasn't specified.")?,
+            x: x.ok_or("--x wasn't specified.")?,
+            y: y.ok_or("--y wasn't specified.")?,
+            title: title.unwrap_or_default(),
+            spec_path: save_spec
+        }),
+        out_path
+    }))
+}
+
+fn compile(args:Plot) -> Result<monet::Graph, Box<dyn Error>> {
+    let read_csv = monet::CsvColumns::read(&args.csv_path)?.columns;
+    let mut raw_points:Vec<(f64, f64)> = Vec::new();
+    for (x, y) in &args.columns {
+        let (Some(xs), Some(ys)) = (read_csv.get(*x), read_csv.get(*y)) else {
+            return Err(format!("The located column, {} and {} doesn't exist.", x, y).into());
+        };
+        raw_points.extend(xs.iter().zip(ys).filter_map(|(x, y)| {
+            Some((args.x.parse_value(x)?, args.y.parse_value(y)?))
+        }));
+    }
+    // f32 can't hold epoch seconds precisely, so time axes plot seconds since their start
+    let x_span = args.x.span(raw_points.iter().map(|p| p.0));
+    let y_span = args.y.span(raw_points.iter().map(|p| p.1));
+    let graph = monet::Graph {
+        graph_paper: GraphPaper {
+            name: args.title,
+            size: monet::graph_paper::A4,
+            points: raw_points.iter()
+                .map(|(x, y)| monet::Vec2::vec2((x - x_span.0) as f32, (y - y_span.0) as f32))
+                .collect(),
+            margin: 100.0,
+            stroke_width: 3.0,
+            great_split_length: 50.0,
+            short_split_length: 25.5,
+        },
+        x_scale: args.x.x_scale(x_span),
+        y_scale: args.y.y_scale(y_span),
+        series: Vec::new()
+    };
+    if let Some(spec_path) = &args.spec_path {
+        save_spec(&graph, &args.csv_path, &args.columns, spec_path)?;
+    }
+    Ok(graph)
+}
+
+/// Saves the spec so that it reads the CSV again instead of holding the points
+fn save_spec(graph:&monet::Graph, csv_path:&Path, columns:&[(usize, usize)], spec_path:&Path) -> Result<(), Box<dyn Error>> {
+    let mut spec = graph.to_spec()?;
+    spec.graph_paper.points.clear();
+    let base = spec_path.parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    spec.csv.push(monet::CsvSource::new(csv_path, base, columns.to_vec()));
+    spec.save(spec_path)?;
+    Ok(())
+}
+
+fn run(args:Args) -> Result<(), Box<dyn Error>> {
+    let (graph, only_spec) = match args.source {
+        Source::Csv(plot) => {
+            let only_spec = plot.spec_path.is_some() && args.out_path.is_none();
+            (compile(plot)?, only_spec)
+        },
+        Source::Spec(path) => (monet::Graph::open_spec(&path)?, false)
+    };
+    // A broken axis would make the report meaningless
+    graph.validate()?;
+    let report = graph.check_points();
+    if !report.is_empty() {
+        eprintln!("{}", report);
+    }
+    if only_spec {
+        return Ok(());
+    }
+    let svg = graph.serialise()?;
+    match &args.out_path {
+        Some(path) => File::create(path)?.write_all(svg.as_bytes())?,
+        None => io::stdout().lock().write_all(svg.as_bytes())?
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            print!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        },
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_axes() {
+        assert_eq!(Axis::parse("linear:0:100"), Ok(Axis {
+            kind: AxisKind::Linear { max_value: 100.0, great_split: GREAT_SPLIT, short_split: SHORT_SPLIT },
+            reversed: false
+        }));
+        assert_eq!(Axis::parse("linear:0:10:5:2:reversed"), Ok(Axis {
+            kind: AxisKind::Linear { max_value: 10.0, great_split: 5, short_split: 2 },
+            reversed: true
+        }));
+        assert_eq!(Axis::parse("log:10:-1:2"), Ok(Axis {
+            kind: AxisKind::Log { base: 10.0, from: -1, to: 2 },
+            reversed: false
+        }));
+        assert_eq!(Axis::parse("time:4"), Ok(Axis {
+            kind: AxisKind::Time { great_split: 4, short_split: SHORT_SPLIT },
+            reversed: false
+        }));
+    }
+
+    #[test]
+    fn rejects_malformed_axes() {
+        for spec in ["linear:0:10:5:2:junk", "linear:1:10", "linear:0", "log:10:-1", "log:10:-1:2:3", "time:1:2:3", "time:x", "polar"] {
+            assert!(Axis::parse(spec).is_err(), "{}", spec);
+        }
+    }
+
+    #[test]
+    fn parses_column_pairs() {
+        assert_eq!(parse_columns("0:1, 0:2"), Ok(vec![(0, 1), (0, 2)]));
+        assert!(parse_columns("0").is_err());
+        assert!(parse_columns("0:a").is_err());
+        assert!(parse_columns("0:1,").is_err());
+    }
+}
@@ -2,15 +2,22 @@
 
 use egui::{Color32, RichText, Ui};
 use rfd;
-use std::{error::Error, path::{self, PathBuf}};
+use std::{error::Error, path::{self, Path, PathBuf}};
 use eframe::egui;
 use csv;
 use monet::{self, GraphPaper};
+use serde::{Deserialize, Serialize};
 
 use std::fs::File;
 use std::io::Write;
 
 const V_SEPARATION:f32 = 20.0;
+const PROJECT_EXTENSION:&str = "monet-project.toml";
+const RECENT_PROJECTS_KEY:&str = "recent_projects";
+const RECENT_PROJECTS_LIMIT:usize = 10;
+const PALETTE:[&str; 6] = [
+    "#4477aa", "#ee6677", "#228833", "#ccbb44", "#66ccee", "#aa3377"
+];
 
 fn main() -> eframe::Result {
     let options = eframe::NativeOptions {
@@ -20,20 +27,96 @@ fn main() -> eframe::Result {
     eframe::run_native(
         "Monet",
         options,
-        Box::new(|_cc| {
-            Ok(Box::<MyApp>::default())
+        Box::new(|cc| {
+            Ok(Box::new(MyApp::new(cc)))
         }),
     )
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 enum AxisKind {
     Linear,
-    Log
+    Log,
+    Time,
+    Category
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ChartKind {
+    Points,
+    Histogram,
+    BoxPlot,
+    Violin
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum BinRule {
+    Width,
+    Count,
+    Sturges,
+    Scott,
+    FreedmanDiaconis
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ExportFormat {
+    Svg,
+    Pdf,
+    Png,
+    Spec
+}
+
+impl ExportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Svg => "svg",
+            ExportFormat::Pdf => "pdf",
+            ExportFormat::Png => "png",
+            ExportFormat::Spec => "monet.toml"
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct HistogramData {
+    rule: BinRule,
+    width: f32,
+    count: u32,
+    density: bool,
+    normal_pdf: bool
+}
+
+impl Default for HistogramData {
+    fn default() -> Self {
+        Self {
+            rule: BinRule::Sturges,
+            width: 1.0,
+            count: 10,
+            density: false,
+            normal_pdf: false
+        }
+    }
+}
+
+impl HistogramData {
+    fn binning(&self) -> monet::Binning {
+        match self.rule {
+            BinRule::Width => monet::Binning::Width(self.width),
+            BinRule::Count => monet::Binning::Count(self.count),
+            BinRule::Sturges => monet::Binning::Sturges,
+            BinRule::Scott => monet::Binning::Scott,
+            BinRule::FreedmanDiaconis => monet::Binning::FreedmanDiaconis
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 struct AxisData {
     axis_kind: AxisKind,
+    reversed: bool,
     // For linear
     h_great_split: u32,
     h_short_split: u32,
@@ -45,29 +128,38 @@ struct AxisData {
     tick: u32
 }
 
-fn read_csv_columns(csv_path: &PathBuf) -> Result<Vec<Vec<String>>, Box<dyn Error>> {
-    let mut rdr = csv::Reader::from_path(csv_path)?;
-
-    let headers = rdr.headers()?.clone();
-    let num_columns = headers.len();
-    let mut columns: Vec<Vec<String>> = vec![Vec::new(); num_columns];
+fn parse_value(axis:&AxisData, field:&str) -> Option<f64> {
+    match axis.axis_kind {
+        AxisKind::Time => monet::math::calendar::parse_timestamp(field),
+        _ => field.trim().parse::<f64>().ok()
+    }
+}
 
-    for result in rdr.records() {
-        let record = result?;
-        for (i, field) in record.iter().enumerate() {
-            if let Some(col) = columns.get_mut(i) {
-                col.push(field.to_string());
-            }
-        }
+/// The span of a time axis. Other axes keep their origin at zero
+fn time_span(axis:&AxisData, values:impl Iterator<Item = f64>) -> (f64, f64) {
+    if axis.axis_kind == AxisKind::Time {
+        monet::spec::time_span(values)
+    } else {
+        (0.0, 0.0)
     }
+}
+
+type SeriesList = Vec<Box<dyn monet::Series>>;
 
-    Ok(columns)
+/// What the CSV yields besides plain points
+#[derive(Default)]
+struct ReadData {
+    x_span: (f64, f64),
+    y_span: (f64, f64),
+    categories: Vec<String>,
+    series: SeriesList
 }
 
 impl Default for AxisData {
     fn default() -> Self {
         Self {
             axis_kind: AxisKind::Linear,
+            reversed: false,
 
             h_great_split: 10,
             h_short_split: 5,
@@ -81,26 +173,78 @@ impl Default for AxisData {
     }
 }
 
+/// Everything needed to pick up where the user left off, saved as TOML
+#[derive(Serialize, Deserialize)]
+struct Project {
+    graph_name: String,
+    // Relative to the project file when they're inside its folder
+    csv_path: Option<PathBuf>,
+    out_path: Option<PathBuf>,
+    font_path: Option<PathBuf>,
+    columns: Vec<(u32, u32)>,
+    x: AxisData,
+    y: AxisData,
+    chart_kind: ChartKind,
+    histogram: HistogramData,
+    bar_layout: monet::series::BarLayout,
+    bar_width: f32,
+    png_dpi: f32,
+    png_transparent: bool
+}
+
+/// Keeps `path` relative to `dir` when it's inside it
+fn relative_to(path:&Path, dir:&Path) -> PathBuf {
+    match (path.canonicalize(), dir.canonicalize()) {
+        (Ok(path), Ok(dir)) => path.strip_prefix(&dir).map(Path::to_path_buf).unwrap_or(path),
+        _ => path.to_path_buf()
+    }
+}
+
 struct MyApp {
     graph_name: String,
+    chart_kind: ChartKind,
+    histogram: HistogramData,
+    bar_layout: monet::series::BarLayout,
+    bar_width: f32,
     csv_path: Option<path::PathBuf>,
+    // Exported as is, in place of the CSV and the settings below
+    spec_path: Option<path::PathBuf>,
     out_path: Option<path::PathBuf>,
+    // Embedded in PDFs so that non-Latin titles render
+    font_path: Option<path::PathBuf>,
+    png_dpi: f32,
+    png_transparent: bool,
     columns: Vec<(u32, u32)>,
     parse_error: String,
+    warning: String,
     x: AxisData,
-    y: AxisData
+    y: AxisData,
+    project_path: Option<path::PathBuf>,
+    // Most recent first, kept across launches in eframe's storage
+    recent_projects: Vec<path::PathBuf>
 }
 
 impl Default for MyApp {
     fn default() -> Self {
         Self {
             graph_name: String::new(),
+            chart_kind: ChartKind::Points,
+            histogram: HistogramData::default(),
+            bar_layout: monet::series::BarLayout::Grouped,
+            bar_width: 0.8,
             csv_path: None,
+            spec_path: None,
             out_path: None,
+            font_path: None,
+            png_dpi: 300.0,
+            png_transparent: false,
             columns: vec![(0, 0)],
             parse_error: String::new(),
+            warning: String::new(),
             x: AxisData::default(),
             y: AxisData::default(),
+            project_path: None,
+            recent_projects: Vec::new(),
         }
     }
 }
@@ -113,6 +257,10 @@ impl eframe::App for MyApp {
 
             ui.add_space(V_SEPARATION);
 
+            self.project(ui);
+
+            ui.add_space(V_SEPARATION);
+
             // Specify csv path
             ui.horizontal(|hui| {
                 if hui.button("Load a CSV").clicked() {
@@ -124,6 +272,23 @@ impl eframe::App for MyApp {
                 })
             });
 
+            // Open a graph spec
+            ui.horizontal(|hui| {
+                if hui.button("Open Spec").clicked()
+                    && let Some(p) = rfd::FileDialog::new()
+                        .add_filter("Graph spec", &["toml", "json"])
+                        .pick_file() {
+                    self.spec_path = Some(p);
+                }
+                hui.label(match &self.spec_path {
+                    Some(s) => s.to_string_lossy().into_owned(),
+                    None => "None".to_string()
+                });
+                if self.spec_path.is_some() && hui.button("Close").clicked() {
+                    self.spec_path = None;
+                }
+            });
+
             // Specify out path
             ui.horizontal(|hui| {
                 if hui.button("Specify Out").clicked() {
@@ -135,6 +300,19 @@ impl eframe::App for MyApp {
                 })
             });
 
+            // Specify the font for PDFs
+            ui.horizontal(|hui| {
+                if hui.button("Choose Font").clicked() {
+                    self.font_path = rfd::FileDialog::new()
+                        .add_filter("Font", &["ttf", "otf", "ttc"])
+                        .pick_file();
+                }
+                hui.label(match &self.font_path {
+                    Some(s) => s.to_string_lossy().into_owned(),
+                    None => "Built-in (PDF: Western European text only)".to_string()
+                })
+            });
+
             ui.add_space(V_SEPARATION);
 
             self.x_axis(ui);
@@ -146,24 +324,169 @@ impl eframe::App for MyApp {
             ui.add_space(V_SEPARATION);
 
 
-            if let Some(p) = &self.csv_path.clone() {
+            self.chart(ui);
+
+            ui.add_space(V_SEPARATION);
+
+            if self.spec_path.is_none() && self.csv_path.is_some() {
                 self.modify_lines(ui);
 
                 ui.add_space(V_SEPARATION);
+            }
 
-                if ui.button("Export SVG").clicked() {
-                    if let Err(e) = self.compile(p) {
-                        self.parse_error = e;
+            if self.spec_path.is_some() || self.csv_path.is_some() {
+                ui.horizontal(|hui| {
+                    hui.add(egui::DragValue::new(&mut self.png_dpi).range(10.0..=2400.0));
+                    hui.label("PNG DPI");
+                    hui.checkbox(&mut self.png_transparent, "Transparent PNG");
+                });
+                ui.horizontal(|hui| {
+                    for (label, format) in [
+                        ("Export SVG", ExportFormat::Svg),
+                        ("Export PDF", ExportFormat::Pdf),
+                        ("Export PNG", ExportFormat::Png),
+                        ("Export Spec", ExportFormat::Spec)
+                    ] {
+                        // An opened spec is already saved
+                        if format == ExportFormat::Spec && self.spec_path.is_some() {
+                            continue;
+                        }
+                        if hui.button(label).clicked() {
+                            if let Err(e) = self.export(format) {
+                                self.parse_error = e;
+                            }
+                        }
                     }
-                }
+                });
             }
 
+            ui.label(RichText::from(&self.warning).color(Color32::YELLOW));
             ui.label(RichText::from(&self.parse_error).color(Color32::LIGHT_RED));
         });
     }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, RECENT_PROJECTS_KEY, &self.recent_projects);
+    }
 }
 
 impl MyApp {
+    fn new(cc:&eframe::CreationContext<'_>) -> Self {
+        Self {
+            recent_projects: cc.storage
+                .and_then(|s| eframe::get_value(s, RECENT_PROJECTS_KEY))
+                .unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+
+    fn project(&mut self, ui:&mut Ui) {
+        ui.horizontal(|hui| {
+            if hui.button("Open Project").clicked()
+                && let Some(p) = rfd::FileDialog::new()
+                    .add_filter("Monet project", &["toml"])
+                    .pick_file() {
+                self.open_project(&p);
+            }
+            if hui.button("Save Project").clicked()
+                && let Some(p) = rfd::FileDialog::new()
+                    .add_filter("Monet project", &["toml"])
+                    .set_file_name(format!("{}.{}", self.project_stem(), PROJECT_EXTENSION))
+                    .save_file() {
+                self.save_project(&p);
+            }
+            hui.add_enabled_ui(!self.recent_projects.is_empty(), |hui| {
+                hui.menu_button("Recent", |ui| {
+                    for p in self.recent_projects.clone() {
+                        if ui.button(p.to_string_lossy()).clicked() {
+                            self.open_project(&p);
+                            ui.close();
+                        }
+                    }
+                });
+            });
+            hui.label(match &self.project_path {
+                Some(s) => s.to_string_lossy().into_owned(),
+                None => "Unsaved".to_string()
+            })
+        });
+    }
+
+    fn open_project(&mut self, path:&Path) {
+        let project = std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|s| toml::from_str::<Project>(&s).map_err(|e| e.to_string()));
+        match project {
+            Ok(project) => {
+                let dir = path.parent().unwrap_or(Path::new("."));
+                self.graph_name = project.graph_name;
+                self.csv_path = project.csv_path.map(|p| dir.join(p));
+                self.out_path = project.out_path.map(|p| dir.join(p));
+                self.font_path = project.font_path.map(|p| dir.join(p));
+                self.columns = project.columns;
+                self.x = project.x;
+                self.y = project.y;
+                self.chart_kind = project.chart_kind;
+                self.histogram = project.histogram;
+                self.bar_layout = project.bar_layout;
+                self.bar_width = project.bar_width;
+                self.png_dpi = project.png_dpi;
+                self.png_transparent = project.png_transparent;
+                // The project's CSV and settings take over from an opened spec
+                self.spec_path = None;
+                self.parse_error.clear();
+                self.remember_project(path);
+            },
+            Err(e) => {
+                self.parse_error = format!("Failed to open the project: {}", e);
+                // A project that can't be opened isn't worth offering again
+                self.recent_projects.retain(|p| p != path);
+            }
+        }
+    }
+
+    /// The proposed file name, so that an untitled graph isn't saved as a hidden file
+    fn project_stem(&self) -> &str {
+        match self.graph_name.trim() {
+            "" => "untitled",
+            name => name
+        }
+    }
+
+    fn save_project(&mut self, path:&Path) {
+        let dir = path.parent().unwrap_or(Path::new("."));
+        let project = Project {
+            graph_name: self.graph_name.clone(),
+            csv_path: self.csv_path.as_deref().map(|p| relative_to(p, dir)),
+            out_path: self.out_path.as_deref().map(|p| relative_to(p, dir)),
+            font_path: self.font_path.as_deref().map(|p| relative_to(p, dir)),
+            columns: self.columns.clone(),
+            x: self.x.clone(),
+            y: self.y.clone(),
+            chart_kind: self.chart_kind.clone(),
+            histogram: self.histogram.clone(),
+            bar_layout: self.bar_layout,
+            bar_width: self.bar_width,
+            png_dpi: self.png_dpi,
+            png_transparent: self.png_transparent
+        };
+        let saved = toml::to_string(&project)
+            .map_err(|e| e.to_string())
+            .and_then(|s| std::fs::write(path, s).map_err(|e| e.to_string()));
+        match saved {
+            Ok(()) => self.remember_project(path),
+            Err(e) => self.parse_error = format!("Failed to save the project: {}", e)
+        }
+    }
+
+    fn remember_project(&mut self, path:&Path) {
+        let path = path.canonicalize().unwrap_or(path.to_path_buf());
+        self.project_path = Some(path.clone());
+        self.recent_projects.retain(|p| *p != path);
+        self.recent_projects.insert(0, path);
+        self.recent_projects.truncate(RECENT_PROJECTS_LIMIT);
+    }
+
     fn x_axis(&mut self, ui:&mut Ui) {
         ui.label("X Axis Property");
         // Select X Axis Type
@@ -180,7 +503,18 @@ impl MyApp {
                 AxisKind::Log,
                 "Log"
             );
+            ui.selectable_value(
+                &mut self.x.axis_kind,
+                AxisKind::Time,
+                "Time"
+            );
+            ui.selectable_value(
+                &mut self.x.axis_kind,
+                AxisKind::Category,
+                "Category"
+            );
         });
+        ui.checkbox(&mut self.x.reversed, "Reversed");
         match self.x.axis_kind {
             AxisKind::Linear => {
                 ui.horizontal(|hui| {
@@ -209,6 +543,36 @@ impl MyApp {
                     hui.add(egui::DragValue::new(&mut self.x.to));
                     hui.label("Max Pow");
                 });
+            },
+            AxisKind::Time => {
+                ui.horizontal(|hui| {
+                    hui.add(egui::DragValue::new(&mut self.x.h_great_split));
+                    hui.label("Great Tick Amount");
+                });
+                ui.horizontal(|hui| {
+                    hui.add(egui::DragValue::new(&mut self.x.h_short_split));
+                    hui.label("Short Tick Amount");
+                });
+            },
+            AxisKind::Category => {
+                egui::ComboBox::from_label("Bar layout")
+                .selected_text(format!("{:?}", self.bar_layout))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(
+                        &mut self.bar_layout,
+                        monet::series::BarLayout::Grouped,
+                        "Grouped"
+                    );
+                    ui.selectable_value(
+                        &mut self.bar_layout,
+                        monet::series::BarLayout::Stacked,
+                        "Stacked"
+                    );
+                });
+                ui.horizontal(|hui| {
+                    hui.add(egui::DragValue::new(&mut self.bar_width).range(0.0..=1.0).speed(0.01));
+                    hui.label("Bar Width");
+                });
             }
         }
     }
@@ -229,7 +593,13 @@ impl MyApp {
                 AxisKind::Log,
                 "Log"
             );
+            ui.selectable_value(
+                &mut self.y.axis_kind,
+                AxisKind::Time,
+                "Time"
+            );
         });
+        ui.checkbox(&mut self.y.reversed, "Reversed");
         match self.y.axis_kind {
             AxisKind::Linear => {
                 ui.horizontal(|hui| {
@@ -258,7 +628,87 @@ impl MyApp {
                     hui.add(egui::DragValue::new(&mut self.y.to));
                     hui.label("Max Pow");
                 });
+            },
+            AxisKind::Time => {
+                ui.horizontal(|hui| {
+                    hui.add(egui::DragValue::new(&mut self.y.h_great_split));
+                    hui.label("Great Tick Amount");
+                });
+                ui.horizontal(|hui| {
+                    hui.add(egui::DragValue::new(&mut self.y.h_short_split));
+                    hui.label("Short Tick Amount");
+                });
+            },
+            // Only offered for X
+            AxisKind::Category => {}
+        }
+    }
+
+    fn chart(&mut self, ui:&mut Ui) {
+        ui.label("Chart Property");
+        egui::ComboBox::from_label("Chart type")
+        .selected_text(format!("{:?}", self.chart_kind))
+        .show_ui(ui, |ui| {
+            ui.selectable_value(
+                &mut self.chart_kind,
+                ChartKind::Points,
+                "Points"
+            );
+            ui.selectable_value(
+                &mut self.chart_kind,
+                ChartKind::Histogram,
+                "Histogram"
+            );
+            ui.selectable_value(
+                &mut self.chart_kind,
+                ChartKind::BoxPlot,
+                "Box plot"
+            );
+            ui.selectable_value(
+                &mut self.chart_kind,
+                ChartKind::Violin,
+                "Violin"
+            );
+        });
+        if self.uses_boxes() {
+            ui.label("Each line groups its second column by the labels in its first");
+            ui.horizontal(|hui| {
+                hui.add(egui::DragValue::new(&mut self.bar_width).range(0.0..=1.0).speed(0.01));
+                hui.label("Box Width");
+            });
+        }
+        if self.chart_kind == ChartKind::Histogram {
+            ui.label("The first column of each line is binned");
+            egui::ComboBox::from_label("Binning")
+            .selected_text(format!("{:?}", self.histogram.rule))
+            .show_ui(ui, |ui| {
+                for (rule, label) in [
+                    (BinRule::Width, "Width"),
+                    (BinRule::Count, "Count"),
+                    (BinRule::Sturges, "Sturges"),
+                    (BinRule::Scott, "Scott"),
+                    (BinRule::FreedmanDiaconis, "Freedman-Diaconis")
+                ] {
+                    ui.selectable_value(&mut self.histogram.rule, rule, label);
+                }
+            });
+            match self.histogram.rule {
+                BinRule::Width => {
+                    ui.horizontal(|hui| {
+                        hui.add(egui::DragValue::new(&mut self.histogram.width).range(0.001..=f32::MAX).speed(0.1));
+                        hui.label("Bin Width");
+                    });
+                },
+                BinRule::Count => {
+                    ui.horizontal(|hui| {
+                        hui.add(egui::DragValue::new(&mut self.histogram.count));
+                        hui.label("Bin Amount");
+                    });
+                },
+                _ => {}
             }
+            ui.checkbox(&mut self.histogram.density, "Density");
+            ui.checkbox(&mut self.histogram.normal_pdf, "Overlay normal distribution");
         }
     }
 
@@ -280,7 +730,31 @@ impl MyApp {
         }
     }
 
-    fn compile(&mut self, csv_path:&PathBuf) -> Result<(), String> {
+    fn export(&mut self, format:ExportFormat) -> Result<(), String> {
+        match (self.spec_path.clone(), self.csv_path.clone()) {
+            (Some(spec_path), _) => self.render_spec(&spec_path, format),
+            (None, Some(csv_path)) => self.compile(&csv_path, format),
+            (None, None) => Ok(())
+        }
+    }
+
+    /// Writes the figure of a spec, named after the spec file
+    fn render_spec(&mut self, spec_path:&Path, format:ExportFormat) -> Result<(), String> {
+        let graph = monet::Graph::open_spec(spec_path).map_err(|e| e.to_string())?;
+        self.warning = graph.check_points().to_string();
+        graph.validate().map_err(|e| e.to_string())?;
+        let Some(p) = &self.out_path else {
+            return Err("Out path wasn't specified".to_string());
+        };
+        let name = spec_path.file_name()
+            .and_then(|n| n.to_str())
+            .map(|n| n.trim_end_matches(".json").trim_end_matches(".toml").trim_end_matches(".monet"))
+            .unwrap_or_default();
+        self.out(&graph, &p.join(format!("{}.{}", name, format.extension())), format)
+            .map_err(|e| e.to_string())
+    }
+
+    fn compile(&mut self, csv_path:&PathBuf, format:ExportFormat) -> Result<(), String> {
         if let Ok(_) = csv::Reader::from_path(csv_path) {
             let mut graph_paper = monet::GraphPaper {
                 name: self.graph_name.clone(),
@@ -292,35 +766,38 @@ impl MyApp {
                 short_split_length: 25.5,
             };
             if let Some(s) = &self.csv_path {
-                let read_csv = read_csv_columns(s)
-                .or(Err("Failed to parse CSV into vector."))?;
-                for i in 0..self.columns.len() {
-                    let c = self.columns.get(i).unwrap();
-                    let x:Option<&Vec<String>> = read_csv.get(c.0 as usize);
-                    let y:Option<&Vec<String>> = read_csv.get(c.1 as usize);
-                    let points = match (x, y) {
-                        (Some(x), Some(y)) => {
-                            x.iter().zip(y)
-                            .filter_map(|f| {
-                                match (f.0.parse::<f32>(), f.1.parse::<f32>()) {
-                                    (Ok(x), Ok(y)) => Some(monet::Vec2::vec2(x, y)),
-                                        (_, _) => None
-                                }
-                            }).collect::<Vec<monet::Vec2>>()
-                        },
-                        (_, _) => return Err(format!(
-                            "The located column, {} and {} doesn't exit.", c.0, c.1
-                        ))
-                    };
-                    graph_paper.points.extend(points);
+                let monet::CsvColumns { headers, columns: read_csv } = monet::CsvColumns::read(s)
+                    .map_err(|e| e.to_string())?;
+                let mut data = ReadData::default();
+                if self.chart_kind == ChartKind::Histogram {
+                    data.series.extend(self.read_histograms(&headers, &read_csv)?);
+                } else if self.uses_boxes() {
+                    let (categories, boxes) = self.read_boxes(&headers, &read_csv)?;
+                    data.categories = categories;
+                    data.series.extend(boxes);
+                } else if self.x.axis_kind == AxisKind::Category {
+                    let (categories, chart) = self.read_bars(&headers, &read_csv)?;
+                    data.categories = categories;
+                    data.series.push(Box::new(chart));
+                } else {
+                    let raw_points = self.read_points(&read_csv)?;
+                    // f32 can't hold epoch seconds precisely, so time axes plot seconds since their start
+                    data.x_span = time_span(&self.x, raw_points.iter().map(|p| p.0));
+                    data.y_span = time_span(&self.y, raw_points.iter().map(|p| p.1));
+                    graph_paper.points.extend(raw_points.iter().map(|(x, y)| {
+                        monet::Vec2::vec2((x - data.x_span.0) as f32, (y - data.y_span.0) as f32)
+                    }));
                 }
-                let serialised = self.serialise(graph_paper);
+                let graph = self.graph(graph_paper, data);
+                graph.validate().map_err(|e| e.to_string())?;
+                self.warning = graph.check_points().to_string();
                 if let Some(p) = &self.out_path {
                     if let Err(e) = self.out(
-                        serialised,
+                        &graph,
                         &p.join(PathBuf::from(
-                            format!("{}.svg", self.graph_name))
-                        )
+                            format!("{}.{}", self.graph_name, format.extension()))
+                        ),
+                        format
                     ) {
                         return Err(e.to_string());
                     };
@@ -334,40 +811,225 @@ impl MyApp {
         Ok(())
     }
 
-    fn serialise(&self, graph_paper:GraphPaper) -> String {
+    fn read_points(&self, read_csv:&[Vec<String>]) -> Result<Vec<(f64, f64)>, String> {
+        let mut raw_points:Vec<(f64, f64)> = Vec::new();
+        for c in &self.columns {
+            let x:Option<&Vec<String>> = read_csv.get(c.0 as usize);
+            let y:Option<&Vec<String>> = read_csv.get(c.1 as usize);
+            let points = match (x, y) {
+                (Some(x), Some(y)) => {
+                    x.iter().zip(y)
+                    .filter_map(|f| {
+                        match (parse_value(&self.x, f.0), parse_value(&self.y, f.1)) {
+                            (Some(x), Some(y)) => Some((x, y)),
+                                (_, _) => None
+                        }
+                    }).collect::<Vec<(f64, f64)>>()
+                },
+                (_, _) => return Err(format!(
+                    "The located column, {} and {} doesn't exit.", c.0, c.1
+                ))
+            };
+            raw_points.extend(points);
+        }
+        Ok(raw_points)
+    }
+
+    fn read_histograms(&self, headers:&[String], read_csv:&[Vec<String>]) -> Result<SeriesList, String> {
+        self.columns.iter()
+            .enumerate()
+            .map(|(i, c)| {
+                let column = read_csv.get(c.0 as usize).ok_or(format!(
+                    "The located column, {} doesn't exit.", c.0
+                ))?;
+                let histogram:Box<dyn monet::Series> = Box::new(monet::Histogram {
+                    name: headers.get(c.0 as usize).cloned().unwrap_or_default(),
+                    values: column.iter()
+                        .filter_map(|v| v.trim().parse::<f32>().ok())
+                        .collect(),
+                    binning: self.histogram.binning(),
+                    normalisation: if self.histogram.density {
+                        monet::Normalisation::Density
+                    } else {
+                        monet::Normalisation::Count
+                    },
+                    fill: PALETTE[i % PALETTE.len()].to_string(),
+                    normal_pdf: self.histogram.normal_pdf
+                });
+                Ok(histogram)
+            })
+            .collect()
+    }
+
+    fn uses_boxes(&self) -> bool {
+        matches!(self.chart_kind, ChartKind::BoxPlot | ChartKind::Violin)
+    }
+
+    /// Every line shares the categories found across all of them
+    fn read_boxes(&self, headers:&[String], read_csv:&[Vec<String>]) -> Result<(Vec<String>, SeriesList), String> {
+        let mut columns = Vec::new();
+        for c in &self.columns {
+            match (read_csv.get(c.0 as usize), read_csv.get(c.1 as usize)) {
+                (Some(x), Some(y)) => columns.push((x, y)),
+                (_, _) => return Err(format!(
+                    "The located column, {} and {} doesn't exit.", c.0, c.1
+                ))
+            }
+        }
+        let categories = monet::XCategoryScale::from_labels(
+            columns.iter().flat_map(|(x, _)| x.iter().map(|l| l.as_str()))
+        ).categories;
+        let boxes = columns.iter()
+            .enumerate()
+            .map(|(i, (x, y))| {
+                let mut groups = vec![Vec::new(); categories.len()];
+                for (label, value) in x.iter().zip(y.iter()) {
+                    if let (Some(index), Ok(value)) = (
+                        categories.iter().position(|c| c == label),
+                        value.trim().parse::<f32>()
+                    ) {
+                        groups[index].push(value);
+                    }
+                }
+                let boxes:Box<dyn monet::Series> = Box::new(monet::BoxPlot {
+                    name: headers.get(self.columns[i].1 as usize).cloned().unwrap_or_default(),
+                    groups,
+                    width: self.bar_width,
+                    fill: PALETTE[i % PALETTE.len()].to_string(),
+                    violin: self.chart_kind == ChartKind::Violin
+                });
+                boxes
+            })
+            .collect();
+        Ok((categories, boxes))
+    }
+
+    /// Each column pair becomes a bar series, summing the values that share a label
+    fn read_bars(&self, headers:&[String], read_csv:&[Vec<String>]) -> Result<(Vec<String>, monet::series::BarChart), String> {
+        let mut columns = Vec::new();
+        for c in &self.columns {
+            match (read_csv.get(c.0 as usize), read_csv.get(c.1 as usize)) {
+                (Some(x), Some(y)) => columns.push((x, y)),
+                (_, _) => return Err(format!(
+                    "The located column, {} and {} doesn't exit.", c.0, c.1
+                ))
+            }
+        }
+        let scale = monet::XCategoryScale::from_labels(
+            columns.iter().flat_map(|(x, _)| x.iter().map(|l| l.as_str()))
+        );
+        let series = columns.iter()
+            .enumerate()
+            .map(|(i, (x, y))| {
+                let mut values = vec![0.0; scale.categories.len()];
+                for (label, value) in x.iter().zip(y.iter()) {
+                    // Bar heights are plain numbers, whatever the y axis kind
+                    if let (Some(index), Ok(value)) = (scale.index(label), value.trim().parse::<f32>()) {
+                        values[index] += value;
+                    }
+                }
+                monet::series::BarSeries {
+                    name: headers.get(self.columns[i].1 as usize).cloned().unwrap_or_default(),
+                    values,
+                    fill: PALETTE[i % PALETTE.len()].to_string()
+                }
+            })
+            .collect();
+        Ok((scale.categories, monet::series::BarChart {
+            series,
+            layout: self.bar_layout,
+            width: self.bar_width
+        }))
+    }
+
+    fn graph(&self, graph_paper:GraphPaper, data:ReadData) -> monet::Graph {
+        let (x_span, y_span) = (data.x_span, data.y_span);
+        let x_kind = if self.uses_boxes() { &AxisKind::Category } else { &self.x.axis_kind };
         monet::graph_paper::Graph {
             graph_paper: graph_paper,
-            x_scale: match self.x.axis_kind {
+            x_scale: match x_kind {
                 AxisKind::Linear => Box::new(monet::XLinearScale {
                     h_great_split: self.x.h_great_split,
                     h_short_split: self.x.h_short_split,
-                    max_value    : self.x.max_value
+                    max_value    : self.x.max_value,
+                    reversed     : self.x.reversed
                 }),
                 AxisKind::Log => Box::new(monet::XLogScale {
                     base: self.x.base,
                     from: self.x.from,
                     to  : self.x.to,
-                    tick: self.x.tick
+                    tick: self.x.tick,
+                    reversed: self.x.reversed
+                }),
+                AxisKind::Time => Box::new(monet::XTimeScale {
+                    start: x_span.0,
+                    end  : x_span.1,
+                    h_great_split: self.x.h_great_split,
+                    h_short_split: self.x.h_short_split,
+                    reversed: self.x.reversed
+                }),
+                AxisKind::Category => Box::new(monet::XCategoryScale {
+                    categories: data.categories,
+                    reversed: self.x.reversed
                 })
             },
             y_scale: match self.y.axis_kind {
-                AxisKind::Linear => Box::new(monet::YLinearScale {
+                AxisKind::Linear | AxisKind::Category => Box::new(monet::YLinearScale {
                     v_great_split: self.y.h_great_split,
                     v_short_split: self.y.h_short_split,
-                    max_value    : self.y.max_value
+                    max_value    : self.y.max_value,
+                    reversed     : self.y.reversed
                 }),
                 AxisKind::Log => Box::new(monet::YLogScale {
                     base: self.y.base,
                     from: self.y.from,
                     to  : self.y.to,
+                    reversed: self.y.reversed
+                }),
+                AxisKind::Time => Box::new(monet::YTimeScale {
+                    start: y_span.0,
+                    end  : y_span.1,
+                    v_great_split: self.y.h_great_split,
+                    v_short_split: self.y.h_short_split,
+                    reversed: self.y.reversed
                 })
-            }
-        }.serialise()
+            },
+            series: data.series
+        }
+    }
+
+    fn font(&self) -> Result<Option<monet::Font>, monet::Error> {
+        self.font_path.as_ref().map(monet::Font::from_file).transpose()
     }
 
-    fn out(&self, serialised:String, path:&PathBuf) -> Result<(), Box<dyn Error>> {
-        let mut file = File::create(path)?;
-        write!(file, "{}", &serialised)?;
+    /// Plain points are saved as a reference to the CSV, series with their values
+    fn save_spec(&self, graph:&monet::Graph, path:&Path) -> Result<(), Box<dyn Error>> {
+        let mut spec = graph.to_spec()?;
+        if let Some(csv_path) = &self.csv_path
+            && !spec.graph_paper.points.is_empty() {
+            spec.graph_paper.points.clear();
+            let columns = self.columns.iter().map(|c| (c.0 as usize, c.1 as usize)).collect();
+            spec.csv.push(monet::CsvSource::new(csv_path, path.parent().unwrap_or(Path::new(".")), columns));
+        }
+        spec.save(path)?;
+        Ok(())
+    }
+
+    fn out(&self, graph:&monet::Graph, path:&PathBuf, format:ExportFormat) -> Result<(), Box<dyn Error>> {
+        let mut file = match format {
+            ExportFormat::Svg => graph.write(File::create(path)?)?,
+            ExportFormat::Pdf => graph.write_pdf(File::create(path)?, self.font()?)?,
+            ExportFormat::Png => {
+                // Without a chosen font monet falls back to its bundled face
+                let options = monet::PngOptions {
+                    resolution: monet::Resolution::Dpi(self.png_dpi),
+                    transparent: self.png_transparent,
+                    font: self.font()?
+                };
+                graph.write_png(File::create(path)?, &options)?
+            },
+            ExportFormat::Spec => return self.save_spec(graph, path)
+        };
         file.flush()?;
         Ok(())
     }
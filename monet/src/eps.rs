@@ -0,0 +1,208 @@
+use std::fmt;
+use std::io::{self, BufWriter, Write};
+
+use crate::error::Error;
+use crate::graph_paper::Graph;
+use crate::math::Vec2;
+use crate::render::{Backend, HorizontalAnchor, PathCommand, Rgb, Style, Text, VerticalAnchor};
+
+/// 用紙の1単位 (0.1mm) をPostScriptのpt (1/72インチ) にする係数
+const UNIT_TO_PT:f32 = 72_f32 / 254_f32;
+// Helveticaの上端 / 下端の高さ (文字の大きさに対する割合)
+const HELVETICA_ASCENDER:f32 = 0.718;
+const HELVETICA_DESCENDER:f32 = -0.207;
+
+/// 描画命令の略記と、Latin-1で符号化したHelvetica
+const PROLOG:&str = "\
+/m { moveto } bind def
+/l { lineto } bind def
+/h { closepath } bind def
+/n { newpath } bind def
+/c { 0 360 arc closepath } bind def
+/re { 4 2 roll moveto 1 index 0 rlineto 0 exch rlineto neg 0 rlineto closepath } bind def
+/f { gsave setrgbcolor fill grestore } bind def
+/s { setlinewidth setrgbcolor stroke } bind def
+/Helvetica findfont dup length dict begin
+\t{ 1 index /FID ne { def } { pop pop } ifelse } forall
+\t/Encoding ISOLatin1Encoding def
+\tcurrentdict
+end /Helvetica-Latin1 exch definefont pop
+";
+
+/// PostScriptの文字列にする。Latin-1にない文字は`?`にする
+struct PsString<'a>(&'a str);
+impl fmt::Display for PsString<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("(")?;
+        for c in self.0.chars() {
+            match c {
+                '(' | ')' | '\\' => write!(f, "\\{}", c)?,
+                ' '..='~' => write!(f, "{}", c)?,
+                _ if (c as u32) < 256 => write!(f, "\\{:03o}", c as u32)?,
+                _ => f.write_str("?")?
+            }
+        }
+        f.write_str(")")
+    }
+}
+
+/// 色を`setrgbcolor`の引数にする
+struct PsColour(Rgb);
+impl fmt::Display for PsColour {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [r, g, b] = self.0.to_unit();
+        write!(f, "{} {} {}", r, g, b)
+    }
+}
+
+/// EPSを要素ごとに書き出す描画先。書き出しは`BufWriter`でまとめて行う
+pub struct EpsBackend<W:Write> {
+    out: BufWriter<W>,
+    depth: usize,
+}
+impl<W:Write> EpsBackend<W> {
+    /// 用紙の大きさをBoundingBoxとして見出しを書き、座標を用紙に合わせる
+    pub fn new(out:W, size:Vec2) -> io::Result<Self> {
+        let mut out = BufWriter::new(out);
+        let (width, height) = (size.x * UNIT_TO_PT, size.y * UNIT_TO_PT);
+        writeln!(out, "%!PS-Adobe-3.0 EPSF-3.0")?;
+        writeln!(out, "%%BoundingBox: 0 0 {} {}", width.ceil(), height.ceil())?;
+        writeln!(out, "%%HiResBoundingBox: 0 0 {} {}", width, height)?;
+        writeln!(out, "%%Creator: monet")?;
+        writeln!(out, "%%DocumentNeededResources: font Helvetica")?;
+        writeln!(out, "%%EndComments")?;
+        writeln!(out, "%%BeginProlog")?;
+        write!(out, "{}", PROLOG)?;
+        writeln!(out, "%%EndProlog")?;
+        writeln!(out, "gsave")?;
+        // 左上を原点とし、下向きをyの正とする用紙の座標に合わせる
+        writeln!(out, "0 {} translate {} {} scale", height, UNIT_TO_PT, -UNIT_TO_PT)?;
+        writeln!(out, "0 setlinecap 0 setlinejoin")?;
+        Ok(EpsBackend { out, depth: 0 })
+    }
+    /// 開いているまとまりを閉じて、書き出し先を返す
+    pub fn finish(mut self) -> io::Result<W> {
+        while self.depth > 0 {
+            self.end_group()?;
+        }
+        writeln!(self.out, "grestore")?;
+        writeln!(self.out, "showpage")?;
+        writeln!(self.out, "%%EOF")?;
+        self.out.into_inner().map_err(|e| e.into_error())
+    }
+    fn trace(&mut self, commands:&[PathCommand]) -> io::Result<()> {
+        write!(self.out, "n")?;
+        for command in commands {
+            match command {
+                PathCommand::MoveTo(p) => write!(self.out, " {} {} m", p.x, p.y)?,
+                PathCommand::LineTo(p) => write!(self.out, " {} {} l", p.x, p.y)?,
+                PathCommand::Close => write!(self.out, " h")?
+            }
+        }
+        Ok(())
+    }
+    /// なぞった輪郭を塗る / 線を引く。
+    /// PostScriptは透明度を持たないので、塗りの不透明度は白と混ぜた色で表す
+    fn paint(&mut self, style:&Style, fillable:bool) -> io::Result<()> {
+        if let Some(colour) = style.fill_colour().filter(|_| fillable) {
+            let colour = match style.fill_opacity {
+                Some(opacity) => {
+                    let blend = |v:u8| (255_f32 - opacity.clamp(0_f32, 1_f32) * (255_f32 - v as f32)).round() as u8;
+                    Rgb { r: blend(colour.r), g: blend(colour.g), b: blend(colour.b) }
+                },
+                None => colour
+            };
+            write!(self.out, " {} f", PsColour(colour))?;
+        }
+        if let Some(colour) = style.stroke_colour() {
+            write!(self.out, " {} {} s", PsColour(colour), style.width())?;
+        }
+        writeln!(self.out)
+    }
+}
+
+impl<W:Write> Backend for EpsBackend<W> {
+    fn begin_group(&mut self, _id:Option<&str>, _class:Option<&str>) -> io::Result<()> {
+        self.depth += 1;
+        writeln!(self.out, "gsave")
+    }
+    fn end_group(&mut self) -> io::Result<()> {
+        // 対応する`gsave`のない`grestore`は用紙の座標の設定まで戻してしまう
+        self.depth = self.depth.checked_sub(1)
+            .ok_or_else(|| io::Error::other("end_group was called without an open group"))?;
+        writeln!(self.out, "grestore")
+    }
+    fn clip(&mut self, commands:&[PathCommand]) -> io::Result<()> {
+        self.trace(commands)?;
+        writeln!(self.out, " clip n")
+    }
+    fn line(&mut self, from:Vec2, to:Vec2, style:&Style) -> io::Result<()> {
+        self.trace(&[PathCommand::MoveTo(from), PathCommand::LineTo(to)])?;
+        self.paint(style, false)
+    }
+    fn polyline(&mut self, points:&[Vec2], closed:bool, style:&Style) -> io::Result<()> {
+        self.trace(&PathCommand::through(points, closed))?;
+        self.paint(style, true)
+    }
+    fn path(&mut self, commands:&[PathCommand], style:&Style) -> io::Result<()> {
+        self.trace(commands)?;
+        self.paint(style, true)
+    }
+    fn rect(&mut self, origin:Vec2, size:Vec2, style:&Style) -> io::Result<()> {
+        write!(self.out, "n {} {} {} {} re", origin.x, origin.y, size.x, size.y)?;
+        self.paint(style, true)
+    }
+    fn circle(&mut self, centre:Vec2, radius:f32, style:&Style) -> io::Result<()> {
+        write!(self.out, "n {} {} {} c", centre.x, centre.y, radius)?;
+        self.paint(style, true)
+    }
+    fn text(&mut self, text:&Text) -> io::Result<()> {
+        let content = text.content();
+        if content.is_empty() {
+            return Ok(());
+        }
+        let size = text.setting.size();
+        // 幅は`stringwidth`で測り、その割合だけ左へずらす
+        let h_shift = match text.setting.h_anchor {
+            None | Some(HorizontalAnchor::Start) => 0_f32,
+            Some(HorizontalAnchor::Centre) => -0.5_f32,
+            Some(HorizontalAnchor::End) => -1_f32
+        };
+        // 文字の向きを戻した座標では上向きが正
+        let v_shift = match text.setting.v_anchor {
+            None | Some(VerticalAnchor::Bottom) => 0_f32,
+            Some(VerticalAnchor::Centre) => -(HELVETICA_ASCENDER + HELVETICA_DESCENDER) / 2_f32 * size,
+            Some(VerticalAnchor::Top) => -HELVETICA_ASCENDER * size
+        };
+        writeln!(
+            self.out,
+            "gsave {} {} translate 1 -1 scale /Helvetica-Latin1 findfont {} scalefont setfont \
+             0 0 0 setrgbcolor {} dup stringwidth pop {} mul {} m show grestore",
+            text.anchor.x, text.anchor.y, size, PsString(&content), h_shift, v_shift
+        )
+    }
+}
+
+impl Graph {
+    /// EPSを`out`へ要素ごとに書き出す。BoundingBoxは用紙の1単位を0.1mmとして決める
+    pub fn write_eps<W:Write>(&self, out:W) -> Result<W, Error> {
+        let mut backend = EpsBackend::new(out, self.graph_paper.size)?;
+        self.render(&mut backend)?;
+        Ok(backend.finish()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unmatched_end_group_is_an_error() {
+        let mut backend = EpsBackend::new(Vec::new(), Vec2::vec2(100_f32, 100_f32)).unwrap();
+        backend.begin_group(None, None).unwrap();
+        backend.end_group().unwrap();
+        assert!(backend.end_group().is_err());
+        let eps = String::from_utf8(backend.finish().unwrap()).unwrap();
+        assert_eq!(eps.matches("gsave").count(), eps.matches("grestore").count());
+    }
+}
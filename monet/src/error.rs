@@ -0,0 +1,104 @@
+use std::fmt::Display;
+
+use crate::math::Vec2;
+use crate::math::ticks::MAX_TICKS;
+
+/// グラフの設定の誤り
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    // 対数の底が1以下
+    InvalidLogBase(f32),
+    // 軸の始点が終点以上
+    EmptyRange { from: f32, to: f32 },
+    // 逆数軸の範囲が0を含む
+    RangeIncludesZero { from: f32, to: f32 },
+    // リニア軸の最大値が0以下
+    NonPositiveMaxValue(f32),
+    // 目盛の分割数が0
+    ZeroSplit,
+    // 目盛の数が上限を超える
+    TooManyTicks(u64),
+    // 余白が用紙の半分以上
+    MarginTooLarge { margin: f32, size: Vec2 },
+    // その他の値の誤り
+    InvalidParameter { name: &'static str, value: f32 },
+    // 出力形式が表せない種類の軸
+    UnsupportedScale(&'static str),
+    // フォントのデータを読めない
+    InvalidFont(String),
+    // 出力先への書き込みの失敗
+    Io { kind: std::io::ErrorKind, message: String },
+    // 設定ファイルの書式の誤り
+    InvalidSpec(String),
+    // 設定ファイルに書けない軸や系列
+    Unserialisable(&'static str),
+    // 読み込むデータの誤り
+    InvalidData(String),
+    // 軸で用紙の位置に写せない点 (対数軸の0以下など)
+    UnmappablePoint(Vec2),
+    // 標準フォントで書けない文字
+    UnencodableText(char),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::InvalidLogBase(base) => write!(
+                f, "The base of a log axis must be greater than 1, but it is {}.", base
+            ),
+            Error::EmptyRange { from, to } => write!(
+                f, "The axis range must start below its end, but it goes from {} to {}.", from, to
+            ),
+            Error::RangeIncludesZero { from, to } => write!(
+                f, "A reciprocal axis can't include zero, but it goes from {} to {}.", from, to
+            ),
+            Error::NonPositiveMaxValue(max_value) => write!(
+                f, "The max value of a linear axis must be positive, but it is {}.", max_value
+            ),
+            Error::ZeroSplit => write!(
+                f, "The amount of ticks must be at least 1."
+            ),
+            Error::TooManyTicks(count) => write!(
+                f, "An axis can have at most {} ticks, but it would have {}.", MAX_TICKS, count
+            ),
+            Error::MarginTooLarge { margin, size } => write!(
+                f, "The margin {} leaves no room to plot on a {} x {} paper.", margin, size.x, size.y
+            ),
+            Error::InvalidParameter { name, value } => write!(
+                f, "{} can't be {}.", name, value
+            ),
+            Error::UnsupportedScale(format) => write!(
+                f, "Only linear and log axes can be written as {}.", format
+            ),
+            Error::InvalidFont(message) => write!(
+                f, "The font couldn't be read: {}", message
+            ),
+            Error::Io { message, .. } => write!(
+                f, "Couldn't write the graph: {}", message
+            ),
+            Error::InvalidSpec(message) => write!(
+                f, "The graph spec is invalid: {}", message
+            ),
+            Error::Unserialisable(name) => write!(
+                f, "The {} can't be saved in a graph spec.", name
+            ),
+            Error::InvalidData(message) => write!(
+                f, "The data couldn't be read: {}", message
+            ),
+            Error::UnmappablePoint(point) => write!(
+                f, "The point ({}, {}) can't be placed on the axes.", point.x, point.y
+            ),
+            Error::UnencodableText(c) => write!(
+                f, "The built-in PDF font can't show {:?}. Choose a font that has it.", c
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Io { kind: error.kind(), message: error.to_string() }
+    }
+}
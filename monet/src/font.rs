@@ -0,0 +1,107 @@
+use std::path::Path;
+use std::sync::Arc;
+#[cfg(feature = "png")]
+use std::sync::OnceLock;
+
+use ttf_parser::{Face, GlyphId};
+
+use crate::error::Error;
+use crate::math::Vec2;
+use crate::render::{HorizontalAnchor, Text, VerticalAnchor};
+
+/// PDFやPNGへの書き出しで使うフォント。TrueType / OpenType (.ttf, .otf, .ttc) を読む
+#[derive(Clone)]
+pub struct Font {
+    data: Arc<Vec<u8>>,
+    // フォントコレクションの中の番号
+    index: u32,
+}
+
+/// 位置を決めた文字列の区切り。`origin`は1文字目のベースラインの左端
+pub(crate) struct PlacedSpan<'a> {
+    pub origin: Vec2,
+    pub text: &'a str,
+}
+
+impl Font {
+    pub fn from_bytes(data:Vec<u8>, index:u32) -> Result<Self, Error> {
+        if let Err(e) = Face::parse(&data, index) {
+            return Err(Error::InvalidFont(e.to_string()));
+        }
+        Ok(Font { data: Arc::new(data), index })
+    }
+    pub fn from_file<P:AsRef<Path>>(path:P) -> Result<Self, Error> {
+        Self::from_bytes(std::fs::read(path)?, 0)
+    }
+    /// 同梱のDejaVu Sans (fonts/LICENSE)。フォントを選ばずにPNGへ書き出すときに使う
+    #[cfg(feature = "png")]
+    pub fn bundled() -> Self {
+        static BUNDLED:OnceLock<Font> = OnceLock::new();
+        BUNDLED.get_or_init(|| {
+            Font::from_bytes(include_bytes!("../fonts/DejaVuSans.ttf").to_vec(), 0)
+                .expect("the bundled font is valid")
+        }).clone()
+    }
+    #[cfg(feature = "pdf")]
+    pub(crate) fn data(&self) -> &[u8] {
+        &self.data
+    }
+    #[cfg(feature = "pdf")]
+    pub(crate) fn index(&self) -> u32 {
+        self.index
+    }
+    pub(crate) fn face(&self) -> Face<'_> {
+        // 作るときに読めることを確かめている
+        Face::parse(&self.data, self.index).expect("font was validated on construction")
+    }
+    /// 文字の字形。フォントにない文字は字形0 (.notdef) になる
+    pub(crate) fn glyph(face:&Face, c:char) -> GlyphId {
+        face.glyph_index(c).unwrap_or(GlyphId(0))
+    }
+    /// `size`の大きさで並べた文字列の幅
+    pub(crate) fn width(face:&Face, text:&str, size:f32) -> f32 {
+        let units = face.units_per_em() as f32;
+        text.chars()
+            .map(|c| face.glyph_hor_advance(Self::glyph(face, c)).unwrap_or(0) as f32)
+            .sum::<f32>() * size / units
+    }
+    /// 揃え方に従って区切りごとの位置を決める
+    pub(crate) fn place<'a>(&self, text:&'a Text) -> Vec<PlacedSpan<'a>> {
+        let face = self.face();
+        let units = face.units_per_em() as f32;
+        let ascender = face.ascender() as f32 / units;
+        let descender = face.descender() as f32 / units;
+        place(text, |s, size| Self::width(&face, s, size), ascender, descender)
+    }
+}
+
+/// 文字の幅を測る関数と、文字の大きさに対する上端 / 下端の高さから区切りの位置を決める
+pub(crate) fn place<'a, F>(text:&'a Text, width:F, ascender:f32, descender:f32) -> Vec<PlacedSpan<'a>>
+    where F: Fn(&str, f32) -> f32
+{
+    let size = text.setting.size();
+    let mut pen = Vec2::vec2(0_f32, 0_f32);
+    let mut spans = Vec::new();
+    for span in &text.spans {
+        pen = pen + Vec2::vec2(span.dx.unwrap_or(0_f32), span.dy.unwrap_or(0_f32));
+        spans.push(PlacedSpan { origin: pen, text: &span.text });
+        pen = pen + Vec2::vec2(width(&span.text, size), 0_f32);
+    }
+    let shift = Vec2::vec2(
+        match text.setting.h_anchor {
+            None | Some(HorizontalAnchor::Start) => 0_f32,
+            Some(HorizontalAnchor::Centre) => -pen.x / 2_f32,
+            Some(HorizontalAnchor::End) => -pen.x
+        },
+        // 下向きが正なので、上端で揃えるならベースラインを下げる
+        match text.setting.v_anchor {
+            None | Some(VerticalAnchor::Bottom) => 0_f32,
+            Some(VerticalAnchor::Centre) => (ascender + descender) / 2_f32 * size,
+            Some(VerticalAnchor::Top) => ascender * size
+        }
+    );
+    for span in spans.iter_mut() {
+        span.origin = text.anchor + span.origin + shift;
+    }
+    spans
+}
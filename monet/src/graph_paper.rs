@@ -1,9 +1,35 @@
 mod linear;
 mod logarithm;
+mod nice;
+mod secondary;
+mod symlog;
+mod reciprocal;
+mod function;
+mod power;
+mod broken;
+mod time;
+mod category;
 
+use std::io::{self, Write};
+
+use crate::error::Error;
 use crate::math::Vec2;
+use crate::math::ticks::MAX_TICKS;
+use crate::series::Series;
+use crate::svg::SvgBackend;
+use crate::render::{
+    Backend,
+    Element,
+    Group,
+    PathCommand,
+    Style,
+    Text,
+    TextSetting,
+    VerticalAnchor,
+    HorizontalAnchor
+};
 
-const P_RADIUS:f32 = 10.0; 
+pub(crate) const P_RADIUS:f32 = 10.0; 
 
 pub use self::linear::{
     XLinearScale,
@@ -15,6 +41,44 @@ pub use self::logarithm::{
     YLogScale
 };
 
+pub use self::symlog::{
+    XSymlogScale,
+    YSymlogScale
+};
+
+pub use self::reciprocal::{
+    XReciprocalScale,
+    YReciprocalScale,
+    TickDomain
+};
+
+pub use self::function::{
+    XFunctionScale,
+    YFunctionScale
+};
+
+pub use self::power::{
+    XPowerScale,
+    YPowerScale
+};
+
+pub use self::broken::{
+    XBrokenScale,
+    YBrokenScale
+};
+
+pub use self::time::{
+    XTimeScale,
+    YTimeScale
+};
+
+pub use self::category::XCategoryScale;
+
+pub use self::secondary::{
+    XSecondaryScale,
+    YSecondaryScale
+};
+
 pub const A4:Vec2 = Vec2 {
     x: 2970.0,
     y: 2100.0
@@ -29,41 +93,138 @@ const YSCALE_TEXT_SETTING:TextSetting = TextSetting {
     v_anchor: Some(VerticalAnchor::Bottom),
     h_anchor: Some(HorizontalAnchor::End)
 };
+const XCATEGORY_TEXT_SETTING:TextSetting = TextSetting {
+    font_size: 20,
+    v_anchor: Some(VerticalAnchor::Top),
+    h_anchor: Some(HorizontalAnchor::Centre)
+};
+const XSECONDARY_TEXT_SETTING:TextSetting = TextSetting {
+    font_size: 20,
+    v_anchor: Some(VerticalAnchor::Bottom),
+    h_anchor: Some(HorizontalAnchor::Start)
+};
+const YSECONDARY_TEXT_SETTING:TextSetting = TextSetting {
+    font_size: 20,
+    v_anchor: Some(VerticalAnchor::Bottom),
+    h_anchor: Some(HorizontalAnchor::Start)
+};
+
+/// 軸の始点からの割合を、軸の向きに合わせて反転する
+fn directed(ratio:f32, reversed:bool) -> f32 {
+    if reversed { 1_f32 - ratio } else { ratio }
+}
+
+/// 目盛の分割数が1以上で、目盛の数が上限以下か確かめる
+fn validate_split(great_split:u32, short_split:u32) -> Result<(), Error> {
+    if great_split == 0 || short_split == 0 {
+        return Err(Error::ZeroSplit);
+    }
+    match great_split.checked_mul(short_split) {
+        Some(total) if total <= MAX_TICKS => Ok(()),
+        _ => Err(Error::TooManyTicks(great_split as u64 * short_split as u64))
+    }
+}
+
+/// `from`から`to`までの辺から区間`gaps`を除いた部分
+fn split_edge(from:f32, to:f32, gaps:&[(f32, f32)]) -> Vec<(f32, f32)> {
+    let mut gaps = gaps.iter()
+        .map(|(a, b)| (a.min(*b), a.max(*b)))
+        .collect::<Vec<(f32, f32)>>();
+    gaps.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut res = Vec::new();
+    let mut start = from;
+    for (a, b) in gaps {
+        if a > start {
+            res.push((start, a.min(to)));
+        }
+        start = start.max(b);
+    }
+    if start < to {
+        res.push((start, to));
+    }
+    res
+}
+
+/// 軸の範囲が有限で、始点が終点より小さいか確かめる
+fn validate_range(from:f32, to:f32) -> Result<(), Error> {
+    if !(from.is_finite() && to.is_finite()) || from >= to {
+        return Err(Error::EmptyRange { from, to });
+    }
+    Ok(())
+}
+
+/// 軸の種類と目盛の値。軸を自前で描く出力形式 (pgfplotsなど) に渡す
+#[derive(Clone, Debug, PartialEq)]
+pub struct AxisLayout {
+    // 対数軸の底。リニア軸なら`None`
+    pub log_base: Option<f32>,
+    // 軸の両端の値
+    pub range: (f32, f32),
+    // 値の増える向きが逆
+    pub reversed: bool,
+    // 長目盛 / 短目盛の値
+    pub great_ticks: Vec<f32>,
+    pub short_ticks: Vec<f32>,
+}
 
 pub trait XScale {
-    fn get_h_splitten(&self, graph_paper:&GraphPaper) -> Vec<String>;
+    fn get_h_splitten(&self, graph_paper:&GraphPaper) -> Vec<Element>;
     fn to_scaled_x<'a>(&'a self, graph_paper:&'a GraphPaper) -> Box<dyn Fn(f32) -> f32 + 'a>;
+    /// 軸の両端の値 (左端, 右端)
+    fn get_x_range(&self) -> (f32, f32);
+    /// 点の値の表示。既定では数値をそのまま書く
+    fn format_x(&self, value:f32) -> String {
+        value.to_string()
+    }
+    /// 枠の上下の辺を途切れさせる区間 (用紙のx座標)。既定では途切れない
+    fn frame_gaps(&self, _graph_paper:&GraphPaper) -> Vec<(f32, f32)> {
+        Vec::new()
+    }
+    /// 軸の種類と目盛の値。リニア軸と対数軸以外は`None`
+    fn layout(&self) -> Option<AxisLayout> {
+        None
+    }
+    /// 設定を確かめる
+    fn validate(&self) -> Result<(), Error> {
+        Ok(())
+    }
+    /// 設定ファイルに書く内容。関数で決まる軸は`None`
+    #[cfg(feature = "spec")]
+    fn spec(&self) -> Option<crate::spec::XScaleSpec> {
+        None
+    }
 }
 pub trait YScale {
-    fn get_v_splitten(&self, graph_paper:&GraphPaper) -> Vec<String>;
+    fn get_v_splitten(&self, graph_paper:&GraphPaper) -> Vec<Element>;
     fn to_scaled_y<'a>(&'a self, graph_paper:&'a GraphPaper) -> Box<dyn Fn(f32) -> f32 + 'a>;
-}
-
-#[derive(Clone)]
-struct SVGHandle {
-    size: Vec2,
-    elements: Vec<String>
-}
-impl SVGHandle {
-    fn serialise(&self) -> String {
-        format!(
-            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">\n{}\n</svg>",
-            self.size.x, self.size.y,
-            self.elements.iter().map(|s| format!("\t{}", s)).collect::<Vec<String>>().join("\n")
-        )
+    /// 軸の両端の値 (下端, 上端)
+    fn get_y_range(&self) -> (f32, f32);
+    /// 点の値の表示。既定では数値をそのまま書く
+    fn format_y(&self, value:f32) -> String {
+        value.to_string()
+    }
+    /// 枠の左右の辺を途切れさせる区間 (用紙のy座標)。既定では途切れない
+    fn frame_gaps(&self, _graph_paper:&GraphPaper) -> Vec<(f32, f32)> {
+        Vec::new()
+    }
+    /// 軸の種類と目盛の値。リニア軸と対数軸以外は`None`
+    fn layout(&self) -> Option<AxisLayout> {
+        None
     }
-    fn add_element(&mut self, element:String) -> &mut Self {
-        self.elements.push(element);
-        self
+    /// 設定を確かめる
+    fn validate(&self) -> Result<(), Error> {
+        Ok(())
     }
-    fn add_elements(&mut self, elements:Vec<String>) -> &mut Self {
-        self.elements.extend(elements);
-        self
+    /// 設定ファイルに書く内容。関数で決まる軸は`None`
+    #[cfg(feature = "spec")]
+    fn spec(&self) -> Option<crate::spec::YScaleSpec> {
+        None
     }
 }
 
 /// グラフ用紙の基底クラス
 #[derive(Clone)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize))]
 pub struct GraphPaper {
     // グラフの名前
     pub name: String,
@@ -72,6 +233,7 @@ pub struct GraphPaper {
     // サイズ
     pub size: Vec2,
     // グラフにプロットする点
+    #[cfg_attr(feature = "spec", serde(default, skip_serializing_if = "Vec::is_empty"))]
     pub points: Vec<Vec2>,
     // 線の太さ
     pub stroke_width: f32,
@@ -80,126 +242,340 @@ pub struct GraphPaper {
     pub short_split_length:f32,
 }
 impl GraphPaper {
-    fn get_margin(&self) -> String {
-        format!(
-            "<rect width=\"{}\" height=\"{}\" fill=\"none\" opacity=\"1\" stroke=\"black\" x=\"{}\" y=\"{}\" stroke-width=\"{}\" />",
-            self.size.x - self.margin * 2_f32, self.size.y - self.margin * 2_f32,
-            self.margin, self.margin, self.stroke_width
-        )
+    /// 用紙の大きさと余白を確かめる
+    pub fn validate(&self) -> Result<(), Error> {
+        for (name, value) in [("size.x", self.size.x), ("size.y", self.size.y)] {
+            if !value.is_finite() || value <= 0_f32 {
+                return Err(Error::InvalidParameter { name, value });
+            }
+        }
+        if !self.margin.is_finite() || self.margin < 0_f32 {
+            return Err(Error::InvalidParameter { name: "margin", value: self.margin });
+        }
+        if self.margin * 2_f32 >= self.size.x.min(self.size.y) {
+            return Err(Error::MarginTooLarge { margin: self.margin, size: self.size });
+        }
+        Ok(())
     }
-    fn get_line(&self, from:Vec2, to:Vec2) -> String {
-        format!(
-            "<line stroke=\"black\" x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke-width=\"{}\" />",
-            from.x, from.y, to.x, to.y, self.stroke_width
-        )
+    /// 枠の線。`x_gaps` / `y_gaps` (用紙の座標の区間) では辺を途切れさせる
+    fn get_margin(&self, x_gaps:&[(f32, f32)], y_gaps:&[(f32, f32)]) -> Element {
+        let lo = Vec2::vec2(self.margin, self.margin);
+        let hi = self.size - lo;
+        if x_gaps.is_empty() && y_gaps.is_empty() {
+            return Element::Rect {
+                origin: lo,
+                size: hi - lo,
+                style: Style::stroke("black", self.stroke_width)
+            };
+        }
+        let mut commands = Vec::new();
+        for y in [lo.y, hi.y] {
+            for (from, to) in split_edge(lo.x, hi.x, x_gaps) {
+                commands.push(PathCommand::MoveTo(Vec2::vec2(from, y)));
+                commands.push(PathCommand::LineTo(Vec2::vec2(to, y)));
+            }
+        }
+        for x in [lo.x, hi.x] {
+            for (from, to) in split_edge(lo.y, hi.y, y_gaps) {
+                commands.push(PathCommand::MoveTo(Vec2::vec2(x, from)));
+                commands.push(PathCommand::LineTo(Vec2::vec2(x, to)));
+            }
+        }
+        Element::Path { commands, style: Style::stroke("black", self.stroke_width) }
     }
-    fn get_text(anchor:Vec2, text:String, extra_property:Option<Vec<String>>) -> String {
-        format!(
-            "<text x=\"{}\" y=\"{}\" {}>{}</text>",
-            anchor.x, anchor.y,
-            if let Some(s) = extra_property {
-                s.join(" ")
-            } else {
-                "".to_string()
-            },
-            text
+    /// プロット領域の輪郭
+    fn get_plot_area(&self) -> Vec<PathCommand> {
+        PathCommand::rect(
+            Vec2::vec2(self.margin, self.margin),
+            self.size - Vec2::vec2(self.margin, self.margin).mul(2_f32)
         )
     }
-    fn to_plot(&self, point:&Vec2) -> String {
-        format!("<circle r=\"{}\" cx=\"{}\" cy=\"{}\" />", P_RADIUS, point.x, point.y)
+    pub(crate) fn get_line(&self, from:Vec2, to:Vec2) -> Element {
+        Element::Line {
+            from,
+            to,
+            style: Style::stroke("black", self.stroke_width)
+        }
+    }
+    fn get_text(anchor:Vec2, text:String, setting:&TextSetting) -> Element {
+        Element::Text(Text::new(anchor, text, *setting))
     }
-    fn get_paper<F>(&self, to_graph_coords:F) -> SVGHandle
-        where F: Fn(Vec2) -> Vec2
+    fn to_plot(&self, point:&Vec2) -> Element {
+        Element::Circle {
+            centre: *point,
+            radius: P_RADIUS,
+            style: Style::default()
+        }
+    }
+    /// プロット点。軸で表せない点は描かない
+    fn get_points<'a, F>(&'a self, to_graph_coords:F) -> impl Iterator<Item = Element> + 'a
+        where F: Fn(Vec2) -> Vec2 + 'a
     {
-        let mut handle = SVGHandle {
-            size: self.size,
-            elements: Vec::new()
-        };
-        handle
-            // 枠を追加
-            .add_element(self.get_margin())
-            // タイトルを追加
-            .add_element(Self::get_text(
-                self.size / Vec2::vec2(2.0, 1.0),
-                self.name.clone(),
-                Some(TextSetting {
-                    font_size: 20,
-                    v_anchor: Some(VerticalAnchor::Bottom),
-                    h_anchor: Some(HorizontalAnchor::Centre)
-                }.serialise())
-            ))
-            // プロット点を追加
-            .add_elements(
-                self.points.iter()
-                    .map(|p| self.to_plot(&to_graph_coords(*p)))
-                    .collect::<Vec<String>>()
-            )
-            .clone()
+        self.points.iter()
+            .map(move |p| to_graph_coords(*p))
+            .filter(|p| p.x.is_finite() && p.y.is_finite())
+            .map(|p| self.to_plot(&p))
+    }
+    /// 点がプロット領域の内側にあるか
+    fn contains(&self, point:Vec2) -> bool {
+        // 軸の端に載った点を丸め誤差で外側と判定しないよう僅かに広げる
+        let epsilon = 1e-3 * self.size.x.max(self.size.y);
+        let (lo, hi) = (self.margin - epsilon, self.size - Vec2::vec2(self.margin, self.margin));
+        lo <= point.x && point.x <= hi.x + epsilon && lo <= point.y && point.y <= hi.y + epsilon
+    }
+    /// 枠とタイトルを描く
+    fn draw_paper(&self, backend:&mut dyn Backend, x_gaps:&[(f32, f32)], y_gaps:&[(f32, f32)]) -> io::Result<()> {
+        backend.draw(&Element::Group(Group::new("frame").with_children(vec![
+                self.get_margin(x_gaps, y_gaps),
+                Self::get_text(
+                    self.size / Vec2::vec2(2.0, 1.0),
+                    self.name.clone(),
+                    &TextSetting {
+                        font_size: 20,
+                        v_anchor: Some(VerticalAnchor::Bottom),
+                        h_anchor: Some(HorizontalAnchor::Centre)
+                    }
+                )
+            ]))
+        )
     }
 }
 
 pub struct Graph {
     pub graph_paper: GraphPaper,
     pub x_scale: Box<dyn XScale>,
-    pub y_scale: Box<dyn YScale>
+    pub y_scale: Box<dyn YScale>,
+    pub series: Vec<Box<dyn Series>>
 }
 
 impl Graph {
-    pub fn serialise(&self) -> String {
+    /// 用紙と両軸の設定と、系列が軸に対して描けるかを確かめる
+    pub fn validate(&self) -> Result<(), Error> {
+        self.graph_paper.validate()?;
+        self.x_scale.validate()?;
+        self.y_scale.validate()?;
+        for series in &self.series {
+            series.validate(&self.graph_paper, self.x_scale.as_ref(), self.y_scale.as_ref())?;
+        }
+        Ok(())
+    }
+    /// 描画先へ用紙、プロット点、系列、目盛の順に描く
+    pub fn render(&self, backend:&mut dyn Backend) -> Result<(), Error> {
+        self.validate()?;
         let x = self.x_scale.to_scaled_x(&self.graph_paper);
         let y = self.y_scale.to_scaled_y(&self.graph_paper);
         let to_graph_coords = |p:Vec2| -> Vec2 {
             Vec2::vec2(x(p.x), y(p.y))
         };
-        self.graph_paper
-            .get_paper(to_graph_coords)
-            // 縦基準線を追加
-            .add_elements(self.y_scale.get_v_splitten(&self.graph_paper))
-            // 横基準線を追加
-            .add_elements(self.x_scale.get_h_splitten(&self.graph_paper))
-            .serialise()
+        self.graph_paper.draw_paper(
+            backend,
+            &self.x_scale.frame_gaps(&self.graph_paper),
+            &self.y_scale.frame_gaps(&self.graph_paper)
+        )?;
+        // プロット点と系列はプロット領域で切り抜く
+        backend.begin_group(Some("plot"), None)?;
+        backend.clip(&self.graph_paper.get_plot_area())?;
+        // 切り抜きの内側をまとめて動かせるようにする (HTMLの拡大など)
+        backend.begin_group(Some("plot-data"), None)?;
+        // プロット点を追加
+        backend.begin_group(Some("points"), Some("points"))?;
+        for point in self.graph_paper.get_points(to_graph_coords) {
+            backend.draw(&point)?;
+        }
+        backend.end_group()?;
+        // 系列を追加
+        for series in &self.series {
+            backend.begin_group(None, Some("series"))?;
+            backend.draw_all(&series.get_plot(&self.graph_paper, self.x_scale.as_ref(), self.y_scale.as_ref()))?;
+            backend.end_group()?;
+        }
+        backend.end_group()?;
+        backend.end_group()?;
+        // 縦基準線を追加
+        backend.draw(&Element::Group(
+            Group::new("y-axis")
+                .with_class("axis")
+                .with_children(self.y_scale.get_v_splitten(&self.graph_paper))
+        ))?;
+        // 横基準線を追加
+        backend.draw(&Element::Group(
+            Group::new("x-axis")
+                .with_class("axis")
+                .with_children(self.x_scale.get_h_splitten(&self.graph_paper))
+        ))?;
+        Ok(())
+    }
+    /// SVGを`out`へ書き出す。プロット点は1つずつ書き、まとめて持たない。
+    /// 目盛と系列は軸や系列ごとに要素を作ってから書く
+    pub fn write<W:Write>(&self, out:W) -> Result<W, Error> {
+        let mut backend = SvgBackend::new(out, self.graph_paper.size)?;
+        self.render(&mut backend)?;
+        Ok(backend.finish()?)
+    }
+    /// SVGを文字列として返す
+    pub fn serialise(&self) -> Result<String, Error> {
+        let buffer = self.write(Vec::new())?;
+        String::from_utf8(buffer).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e).into())
+    }
+    /// プロット点のうち、軸の範囲外のものと軸で表せないものを調べる
+    pub fn check_points(&self) -> PointReport {
+        let x = self.x_scale.to_scaled_x(&self.graph_paper);
+        let y = self.y_scale.to_scaled_y(&self.graph_paper);
+        let mut report = PointReport::default();
+        for (i, p) in self.graph_paper.points.iter().enumerate() {
+            let mapped = Vec2::vec2(x(p.x), y(p.y));
+            if !(mapped.x.is_finite() && mapped.y.is_finite()) {
+                report.invalid.push(i);
+            } else if !self.graph_paper.contains(mapped) {
+                report.outside.push(i);
+            }
+        }
+        report
     }
 }
 
-#[allow(dead_code)]
-enum VerticalAnchor {
-    Top,
-    Centre,
-    Bottom
+/// `Graph::check_points`の結果。`GraphPaper::points`の添字を持つ
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PointReport {
+    // 軸の範囲外で切り抜かれる点
+    pub outside: Vec<usize>,
+    // 軸で表せない点 (対数軸での0以下の値など)
+    pub invalid: Vec<usize>,
 }
-enum HorizontalAnchor {
-    Start,
-    Centre,
-    End
+impl PointReport {
+    pub fn is_empty(&self) -> bool {
+        self.outside.is_empty() && self.invalid.is_empty()
+    }
 }
-struct TextSetting {
-    font_size: u32,
-    v_anchor : Option<VerticalAnchor>,
-    h_anchor : Option<HorizontalAnchor>,
+impl std::fmt::Display for PointReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut messages = Vec::new();
+        if !self.outside.is_empty() {
+            messages.push(format!("{} points outside the axis range", self.outside.len()));
+        }
+        if !self.invalid.is_empty() {
+            messages.push(format!(
+                "{} points not representable on the axes (e.g. non-positive on a log axis)",
+                self.invalid.len()
+            ));
+        }
+        write!(f, "{}", messages.join(", "))
+    }
 }
-impl TextSetting {
-    fn serialise(&self) -> Vec<String> {
-        let mut setting = vec![
-            format!("font-size=\"{}pt\"", self.font_size)
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph_paper(points:Vec<Vec2>) -> GraphPaper {
+        GraphPaper {
+            name: String::new(),
+            margin: 100_f32,
+            size: Vec2::vec2(1100_f32, 1100_f32),
+            points,
+            stroke_width: 1_f32,
+            great_split_length: 20_f32,
+            short_split_length: 10_f32
+        }
+    }
+
+    fn linear(h_great_split:u32, h_short_split:u32) -> XLinearScale {
+        XLinearScale { h_great_split, h_short_split, max_value: 10_f32, reversed: false }
+    }
+
+    #[test]
+    fn split_is_bounded() {
+        assert_eq!(linear(5, 2).validate(), Ok(()));
+        assert_eq!(linear(0, 2).validate(), Err(Error::ZeroSplit));
+        assert_eq!(linear(100, 100).validate(), Ok(()));
+        assert_eq!(linear(100, 101).validate(), Err(Error::TooManyTicks(10100)));
+        // u32で掛けると溢れる組
+        assert_eq!(linear(u32::MAX, 2).validate(), Err(Error::TooManyTicks(u32::MAX as u64 * 2)));
+        let graph = Graph {
+            graph_paper: graph_paper(Vec::new()),
+            x_scale: Box::new(linear(u32::MAX, 2)),
+            y_scale: Box::new(YLinearScale { v_great_split: 5, v_short_split: 2, max_value: 10_f32, reversed: false }),
+            series: Vec::new()
+        };
+        assert!(matches!(graph.serialise(), Err(Error::TooManyTicks(_))));
+    }
+
+    #[test]
+    fn empty_categories_are_invalid() {
+        assert!(XCategoryScale::from_labels(Vec::<&str>::new()).validate().is_err());
+        assert_eq!(XCategoryScale::from_labels(["a"]).validate(), Ok(()));
+    }
+
+    #[test]
+    fn edge_skips_gaps() {
+        assert_eq!(split_edge(0_f32, 10_f32, &[]), vec![(0_f32, 10_f32)]);
+        assert_eq!(split_edge(0_f32, 10_f32, &[(6_f32, 7_f32), (3_f32, 2_f32)]), vec![(0_f32, 2_f32), (3_f32, 6_f32), (7_f32, 10_f32)]);
+    }
+
+    #[test]
+    fn broken_axis_leaves_frame_open() {
+        let graph = Graph {
+            graph_paper: graph_paper(Vec::new()),
+            x_scale: Box::new(XBrokenScale {
+                segments: vec![(0_f32, 10_f32), (90_f32, 100_f32)],
+                gap: 0.1,
+                h_great_split: 2,
+                h_short_split: 2,
+                reversed: false
+            }),
+            y_scale: Box::new(YLinearScale { v_great_split: 5, v_short_split: 2, max_value: 10_f32, reversed: false }),
+            series: Vec::new()
+        };
+        // 区切りは軸の中央 (x=550) で幅90
+        assert_eq!(graph.x_scale.frame_gaps(&graph.graph_paper), vec![(505_f32, 595_f32)]);
+        let svg = graph.serialise().unwrap();
+        // 枠を白で塗り潰さない
+        assert!(!svg.contains("white"), "{}", svg);
+        assert!(svg.contains("M100,100 L505,100 M595,100 L1000,100 M100,1000 L505,1000"), "{}", svg);
+    }
+    #[test]
+    fn points_off_the_axes_are_reported() {
+        let points = vec![
+            Vec2::vec2(5_f32, 10_f32),
+            Vec2::vec2(11_f32, 10_f32),
+            Vec2::vec2(5_f32, 0_f32),
+            Vec2::vec2(5_f32, -1_f32),
+            Vec2::vec2(f32::NAN, 10_f32),
+            Vec2::vec2(5_f32, 1000_f32),
         ];
-        if let Some(s) = &self.v_anchor {
-            setting.push(
-                format!("dominant-baseline=\"{}\"", match s {
-                    VerticalAnchor::Top => "hanging",
-                    VerticalAnchor::Centre => "middle",
-                    VerticalAnchor::Bottom => "auto"
-                })
-            );
+        let graph = Graph {
+            graph_paper: graph_paper(points),
+            x_scale: Box::new(linear(5, 2)),
+            y_scale: Box::new(YLogScale { base: 10_f32, from: 0, to: 2, reversed: false }),
+            series: Vec::new()
         };
-        if let Some(s) = &self.h_anchor {
-            setting.push(
-                format!("text-anchor=\"{}\"", match s {
-                    HorizontalAnchor::Start => "start",
-                    HorizontalAnchor::Centre => "center",
-                    HorizontalAnchor::End => "end"
-                })
-            );
+        let report = graph.check_points();
+        assert_eq!(report, PointReport { outside: vec![1, 5], invalid: vec![2, 3, 4] });
+        assert_eq!(
+            report.to_string(),
+            "2 points outside the axis range, 3 points not representable on the axes (e.g. non-positive on a log axis)"
+        );
+        let fine = Graph { graph_paper: graph_paper(vec![Vec2::vec2(0_f32, 1_f32)]), ..graph };
+        assert!(fine.check_points().is_empty());
+    }
+    #[test]
+    fn clip_paths_are_defined_in_defs() {
+        let graph = Graph {
+            graph_paper: graph_paper(Vec::new()),
+            x_scale: Box::new(linear(5, 2)),
+            y_scale: Box::new(YLinearScale { v_great_split: 5, v_short_split: 2, max_value: 10_f32, reversed: false }),
+            series: Vec::new()
         };
-        setting
+        let svg = graph.serialise().unwrap();
+        assert!(svg.contains(
+            "\t\t<defs>\n\t\t\t<clipPath id=\"plot-clip\">\n\t\t\t\t<path d=\"M100,100 L1000,100 L1000,1000 L100,1000 Z\" />"
+        ), "{}", svg);
+        assert!(svg.contains("<g clip-path=\"url(#plot-clip)\">"), "{}", svg);
+        // 参照の仕組みがない出力形式は定義を描かない
+        let eps = crate::eps::EpsBackend::new(Vec::new(), graph.graph_paper.size).unwrap();
+        let before = eps.finish().unwrap();
+        let mut eps = crate::eps::EpsBackend::new(Vec::new(), graph.graph_paper.size).unwrap();
+        eps.draw(&Element::Defs(vec![Element::ClipPath { id: "a".to_string(), commands: graph.graph_paper.get_plot_area() }])).unwrap();
+        assert_eq!(eps.finish().unwrap(), before);
     }
-}
\ No newline at end of file
+}
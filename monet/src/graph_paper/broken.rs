@@ -0,0 +1,229 @@
+use crate::error::Error;
+use crate::render::Element;
+
+use super::nice::generate_ticks;
+
+use super::{
+    XScale, YScale,
+    GraphPaper, Vec2,
+    directed,
+    validate_split,
+    validate_range,
+    XSCALE_TEXT_SETTING,
+    YSCALE_TEXT_SETTING,
+};
+
+/// 区間の並びと区切りの幅から、軸の始点からの割合を求める
+fn to_ratio(segments:&[(f32, f32)], gap:f32, p:f32) -> f32 {
+    let count = segments.len();
+    let total = segments.iter().map(|(a, b)| b - a).sum::<f32>();
+    let usable = 1_f32 - gap * count.saturating_sub(1) as f32;
+    let mut start = 0_f32;
+    for (i, (a, b)) in segments.iter().enumerate() {
+        let width = usable * (b - a) / total;
+        // 最初と最後の区間は外側へ延長して扱う
+        if (*a <= p && p <= *b) || (i == 0 && p < *a) || (i + 1 == count && p > *b) {
+            return start + (p - a) / (b - a) * width;
+        }
+        start += width + gap;
+    }
+    // 区切りの中の値は表せない
+    f32::NAN
+}
+
+/// 区間が1つ以上あり、小さい順に重ならず並び、区切りが軸に収まるか確かめる
+fn validate(segments:&[(f32, f32)], gap:f32) -> Result<(), Error> {
+    if segments.is_empty() {
+        return Err(Error::InvalidParameter { name: "segments.len()", value: 0_f32 });
+    }
+    for (from, to) in segments {
+        validate_range(*from, *to)?;
+    }
+    for pair in segments.windows(2) {
+        validate_range(pair[0].1, pair[1].0)?;
+    }
+    let gaps = gap * (segments.len() - 1) as f32;
+    if !gap.is_finite() || gap < 0_f32 || gaps >= 1_f32 {
+        return Err(Error::InvalidParameter { name: "gap", value: gap });
+    }
+    Ok(())
+}
+
+/// 区切りの中央の、軸の始点からの割合
+fn break_ratios(segments:&[(f32, f32)], gap:f32) -> Vec<f32> {
+    let count = segments.len();
+    let total = segments.iter().map(|(a, b)| b - a).sum::<f32>();
+    let usable = 1_f32 - gap * count.saturating_sub(1) as f32;
+    let mut start = 0_f32;
+    let mut res = Vec::new();
+    for (a, b) in segments.iter().take(count.saturating_sub(1)) {
+        start += usable * (b - a) / total;
+        res.push(start + gap / 2_f32);
+        start += gap;
+    }
+    res
+}
+
+/// 枠の途切れた`from`と`to`に斜線を引く
+fn get_break_mark(graph_paper:&GraphPaper, from:Vec2, to:Vec2, slash:Vec2) -> Vec<Element> {
+    vec![
+        graph_paper.get_line(from - slash, from + slash),
+        graph_paper.get_line(to - slash, to + slash),
+    ]
+}
+
+/// 値の範囲を区切って繋げたX軸
+#[derive(Clone)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize))]
+pub struct XBrokenScale {
+    // 表示する区間 (始点, 終点) を小さい順に並べる
+    pub segments: Vec<(f32, f32)>,
+    // 区切りの幅 (軸の長さに対する割合)
+    pub gap: f32,
+    // 区間ごとの長目盛のおおよその分割数 / 長目盛の短目盛での分割数
+    pub h_great_split: u32,
+    pub h_short_split: u32,
+    // 右から左へ値が増える
+    pub reversed: bool,
+}
+
+impl XScale for XBrokenScale {
+    fn get_h_splitten(&self, graph_paper:&GraphPaper) -> Vec<Element> {
+        let x = self.to_scaled_x(graph_paper);
+        let mut res = self.segments.iter()
+            .flat_map(|segment| generate_ticks(
+                graph_paper,
+                *segment,
+                &|v:f32| v,
+                &|v:f32| v,
+                self.h_great_split,
+                self.h_short_split,
+                |i:f32| Vec2 {
+                    x: x(i),
+                    y: graph_paper.size.y - graph_paper.margin
+                },
+                |from, scale_length:f32| {
+                    from - Vec2::vec2(0_f32, scale_length)
+                },
+                &XSCALE_TEXT_SETTING
+            ))
+            .collect::<Vec<Element>>();
+        let slash = Vec2::vec2(graph_paper.short_split_length, -graph_paper.short_split_length).mul(0.5);
+        for (from, to) in self.frame_gaps(graph_paper) {
+            for y in [graph_paper.margin, graph_paper.size.y - graph_paper.margin] {
+                res.extend(get_break_mark(graph_paper, Vec2::vec2(from, y), Vec2::vec2(to, y), slash));
+            }
+        }
+        res
+    }
+    fn frame_gaps(&self, graph_paper:&GraphPaper) -> Vec<(f32, f32)> {
+        let size = graph_paper.size.x - 2_f32 * graph_paper.margin;
+        let half_gap = self.gap * size / 2_f32;
+        break_ratios(&self.segments, self.gap)
+            .into_iter()
+            .map(|ratio| {
+                let centre = graph_paper.margin + directed(ratio, self.reversed) * size;
+                (centre - half_gap, centre + half_gap)
+            })
+            .collect()
+    }
+    fn to_scaled_x<'a>(&'a self, graph_paper:&'a GraphPaper) -> Box<dyn Fn(f32) -> f32 + 'a> {
+        Box::new(|x:f32| -> f32 {
+            let size = graph_paper.size.x - 2_f32 * graph_paper.margin;
+            graph_paper.margin + directed(to_ratio(&self.segments, self.gap, x), self.reversed) * size
+        })
+    }
+    fn get_x_range(&self) -> (f32, f32) {
+        (
+            self.segments.first().map_or(0_f32, |s| s.0),
+            self.segments.last().map_or(0_f32, |s| s.1)
+        )
+    }
+    fn validate(&self) -> Result<(), Error> {
+        validate_split(self.h_great_split, self.h_short_split)?;
+        validate(&self.segments, self.gap)
+    }
+    #[cfg(feature = "spec")]
+    fn spec(&self) -> Option<crate::spec::XScaleSpec> {
+        Some(crate::spec::XScaleSpec::Broken(self.clone()))
+    }
+}
+
+/// 値の範囲を区切って繋げたY軸
+#[derive(Clone)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize))]
+pub struct YBrokenScale {
+    // 表示する区間 (始点, 終点) を小さい順に並べる
+    pub segments: Vec<(f32, f32)>,
+    // 区切りの幅 (軸の長さに対する割合)
+    pub gap: f32,
+    // 区間ごとの長目盛のおおよその分割数 / 長目盛の短目盛での分割数
+    pub v_great_split: u32,
+    pub v_short_split: u32,
+    // 上から下へ値が増える
+    pub reversed: bool,
+}
+
+impl YScale for YBrokenScale {
+    fn get_v_splitten(&self, graph_paper:&GraphPaper) -> Vec<Element> {
+        let y = self.to_scaled_y(graph_paper);
+        let mut res = self.segments.iter()
+            .flat_map(|segment| generate_ticks(
+                graph_paper,
+                *segment,
+                &|v:f32| v,
+                &|v:f32| v,
+                self.v_great_split,
+                self.v_short_split,
+                |i:f32| Vec2 {
+                    x: graph_paper.margin,
+                    y: y(i)
+                },
+                |from, scale_length:f32| {
+                    from + Vec2::vec2(scale_length, 0_f32)
+                },
+                &YSCALE_TEXT_SETTING
+            ))
+            .collect::<Vec<Element>>();
+        // X軸の斜線と逆向きに傾ける
+        let slash = Vec2::vec2(graph_paper.short_split_length, graph_paper.short_split_length).mul(0.5);
+        for (from, to) in self.frame_gaps(graph_paper) {
+            for x in [graph_paper.margin, graph_paper.size.x - graph_paper.margin] {
+                res.extend(get_break_mark(graph_paper, Vec2::vec2(x, from), Vec2::vec2(x, to), slash));
+            }
+        }
+        res
+    }
+    fn frame_gaps(&self, graph_paper:&GraphPaper) -> Vec<(f32, f32)> {
+        let size = graph_paper.size.y - 2_f32 * graph_paper.margin;
+        let half_gap = self.gap * size / 2_f32;
+        break_ratios(&self.segments, self.gap)
+            .into_iter()
+            .map(|ratio| {
+                let centre = graph_paper.size.y - graph_paper.margin - directed(ratio, self.reversed) * size;
+                (centre - half_gap, centre + half_gap)
+            })
+            .collect()
+    }
+    fn to_scaled_y<'a>(&'a self, graph_paper:&'a GraphPaper) -> Box<dyn Fn(f32) -> f32 + 'a> {
+        Box::new(|y:f32| -> f32 {
+            let size = graph_paper.size.y - 2_f32 * graph_paper.margin;
+            graph_paper.size.y - graph_paper.margin
+                - directed(to_ratio(&self.segments, self.gap, y), self.reversed) * size
+        })
+    }
+    fn get_y_range(&self) -> (f32, f32) {
+        (
+            self.segments.first().map_or(0_f32, |s| s.0),
+            self.segments.last().map_or(0_f32, |s| s.1)
+        )
+    }
+    fn validate(&self) -> Result<(), Error> {
+        validate_split(self.v_great_split, self.v_short_split)?;
+        validate(&self.segments, self.gap)
+    }
+    #[cfg(feature = "spec")]
+    fn spec(&self) -> Option<crate::spec::YScaleSpec> {
+        Some(crate::spec::YScaleSpec::Broken(self.clone()))
+    }
+}
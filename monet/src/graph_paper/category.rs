@@ -0,0 +1,86 @@
+use crate::error::Error;
+use crate::render::Element;
+
+use super::{
+    XScale,
+    GraphPaper, Vec2,
+    directed,
+    XCATEGORY_TEXT_SETTING,
+};
+
+/// X軸のカテゴリ軸。`i`番目のカテゴリは値`i`の位置を中心とした幅1の区間を占める
+#[derive(Clone)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize))]
+pub struct XCategoryScale {
+    pub categories: Vec<String>,
+    // 右から左へカテゴリを並べる
+    pub reversed: bool,
+}
+impl XCategoryScale {
+    /// CSVの列などから、出現順に重複を除いてカテゴリを作る
+    pub fn from_labels<'a, I>(labels:I) -> Self
+        where I: IntoIterator<Item = &'a str>
+    {
+        let mut categories:Vec<String> = Vec::new();
+        for label in labels {
+            if !categories.iter().any(|c| c == label) {
+                categories.push(label.to_string());
+            }
+        }
+        XCategoryScale { categories, reversed: false }
+    }
+    /// カテゴリの位置 (点の値)
+    pub fn index(&self, label:&str) -> Option<usize> {
+        self.categories.iter().position(|c| c == label)
+    }
+}
+
+impl XScale for XCategoryScale {
+    fn get_h_splitten(&self, graph_paper:&GraphPaper) -> Vec<Element> {
+        let x = self.to_scaled_x(graph_paper);
+        let bottom = graph_paper.size.y - graph_paper.margin;
+        let boundaries = (0..self.categories.len() + 1)
+            .map(|i| {
+                let from = Vec2::vec2(x(i as f32 - 0.5), bottom);
+                let to = from - Vec2::vec2(0_f32, graph_paper.short_split_length);
+                graph_paper.get_line(from, to)
+            });
+        let labels = self.categories.iter()
+            .enumerate()
+            .map(|(i, label)| GraphPaper::get_text(
+                Vec2::vec2(x(i as f32), bottom),
+                label.clone(),
+                &XCATEGORY_TEXT_SETTING
+            ));
+        boundaries.chain(labels).collect::<Vec<Element>>()
+    }
+    fn to_scaled_x<'a>(&'a self, graph_paper:&'a GraphPaper) -> Box<dyn Fn(f32) -> f32 + 'a> {
+        Box::new(|x:f32| -> f32 {
+            let size = graph_paper.size.x - 2_f32 * graph_paper.margin;
+            let count = self.categories.len().max(1) as f32;
+            graph_paper.margin + directed((x + 0.5) / count, self.reversed) * size
+        })
+    }
+    fn get_x_range(&self) -> (f32, f32) {
+        (-0.5, self.categories.len() as f32 - 0.5)
+    }
+    fn validate(&self) -> Result<(), Error> {
+        if self.categories.is_empty() {
+            return Err(Error::InvalidParameter { name: "categories.len()", value: 0_f32 });
+        }
+        Ok(())
+    }
+    /// 値を含むカテゴリの名前。範囲外なら数値のまま書く
+    fn format_x(&self, value:f32) -> String {
+        let index = value.round();
+        if (0_f32..self.categories.len() as f32).contains(&index) {
+            self.categories[index as usize].clone()
+        } else {
+            value.to_string()
+        }
+    }
+    #[cfg(feature = "spec")]
+    fn spec(&self) -> Option<crate::spec::XScaleSpec> {
+        Some(crate::spec::XScaleSpec::Category(self.clone()))
+    }
+}
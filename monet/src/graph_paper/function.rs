@@ -0,0 +1,153 @@
+use crate::error::Error;
+use crate::render::Element;
+
+use super::nice::generate_transformed_ticks;
+
+use super::{
+    XScale, YScale,
+    GraphPaper, Vec2,
+    directed,
+    validate_split,
+    validate_range,
+    XSCALE_TEXT_SETTING,
+    YSCALE_TEXT_SETTING,
+};
+
+/// `forward`で変換した値に比例した位置を返す
+pub(super) fn to_value<'a, F>(
+    graph_paper: &'a GraphPaper,
+    forward: F,
+    range: (f32, f32),
+    reversed: bool,
+    size: f32
+) -> Box<dyn Fn(f32) -> f32 + 'a>
+    where F: Fn(f32) -> f32 + 'a
+{
+    let (lo, hi) = (forward(range.0), forward(range.1));
+    Box::new(move |p:f32| -> f32 {
+        graph_paper.margin + directed((forward(p) - lo) / (hi - lo), reversed) * size
+    })
+}
+
+/// 範囲の両端が変換後も有限で、異なる値になるか確かめる
+pub(super) fn validate_function<F>(forward:F, min_value:f32, max_value:f32) -> Result<(), Error>
+    where F: Fn(f32) -> f32
+{
+    validate_range(min_value, max_value)?;
+    for value in [min_value, max_value] {
+        if !forward(value).is_finite() {
+            return Err(Error::InvalidParameter { name: "forward", value });
+        }
+    }
+    if forward(min_value) == forward(max_value) {
+        return Err(Error::EmptyRange { from: forward(min_value), to: forward(max_value) });
+    }
+    Ok(())
+}
+
+/// 任意の単調な関数で値を変換するX軸
+pub struct XFunctionScale {
+    // 値 → 軸上の量 / 軸上の量 → 値
+    pub forward: Box<dyn Fn(f32) -> f32>,
+    pub inverse: Box<dyn Fn(f32) -> f32>,
+    // 軸の最小値 / 最大値
+    pub min_value: f32,
+    pub max_value: f32,
+    // 長目盛のおおよその分割数 / 長目盛の短目盛での分割数
+    pub h_great_split: u32,
+    pub h_short_split: u32,
+    // 右から左へ値が増える
+    pub reversed: bool,
+}
+
+impl XScale for XFunctionScale {
+    fn get_h_splitten(&self, graph_paper:&GraphPaper) -> Vec<Element> {
+        let x = self.to_scaled_x(graph_paper);
+        generate_transformed_ticks(
+            graph_paper,
+            self.get_x_range(),
+            &self.forward,
+            &self.inverse,
+            self.h_great_split,
+            self.h_short_split,
+            |i:f32| Vec2 {
+                x: x(i),
+                y: graph_paper.size.y - graph_paper.margin
+            },
+            |from, scale_length:f32| {
+                from - Vec2::vec2(0_f32, scale_length)
+            },
+            &XSCALE_TEXT_SETTING
+        )
+    }
+    fn to_scaled_x<'a>(&'a self, graph_paper:&'a GraphPaper) -> Box<dyn Fn(f32) -> f32 + 'a> {
+        to_value(
+            graph_paper,
+            &self.forward,
+            self.get_x_range(),
+            self.reversed,
+            graph_paper.size.x - 2_f32 * graph_paper.margin
+        )
+    }
+    fn get_x_range(&self) -> (f32, f32) {
+        (self.min_value, self.max_value)
+    }
+    fn validate(&self) -> Result<(), Error> {
+        validate_split(self.h_great_split, self.h_short_split)?;
+        validate_function(&self.forward, self.min_value, self.max_value)
+    }
+}
+
+/// 任意の単調な関数で値を変換するY軸
+pub struct YFunctionScale {
+    // 値 → 軸上の量 / 軸上の量 → 値
+    pub forward: Box<dyn Fn(f32) -> f32>,
+    pub inverse: Box<dyn Fn(f32) -> f32>,
+    // 軸の最小値 / 最大値
+    pub min_value: f32,
+    pub max_value: f32,
+    // 長目盛のおおよその分割数 / 長目盛の短目盛での分割数
+    pub v_great_split: u32,
+    pub v_short_split: u32,
+    // 上から下へ値が増える
+    pub reversed: bool,
+}
+
+impl YScale for YFunctionScale {
+    fn get_v_splitten(&self, graph_paper:&GraphPaper) -> Vec<Element> {
+        let y = self.to_scaled_y(graph_paper);
+        generate_transformed_ticks(
+            graph_paper,
+            self.get_y_range(),
+            &self.forward,
+            &self.inverse,
+            self.v_great_split,
+            self.v_short_split,
+            |i:f32| Vec2 {
+                x: graph_paper.margin,
+                y: y(i)
+            },
+            |from, scale_length:f32| {
+                from + Vec2::vec2(scale_length, 0_f32)
+            },
+            &YSCALE_TEXT_SETTING
+        )
+    }
+    fn to_scaled_y<'a>(&'a self, graph_paper:&'a GraphPaper) -> Box<dyn Fn(f32) -> f32 + 'a> {
+        let to_value = to_value(
+            graph_paper,
+            &self.forward,
+            self.get_y_range(),
+            self.reversed,
+            graph_paper.size.y - 2_f32 * graph_paper.margin
+        );
+        Box::new(move |f:f32| graph_paper.size.y - to_value(f))
+    }
+    fn get_y_range(&self) -> (f32, f32) {
+        (self.min_value, self.max_value)
+    }
+    fn validate(&self) -> Result<(), Error> {
+        validate_split(self.v_great_split, self.v_short_split)?;
+        validate_function(&self.forward, self.min_value, self.max_value)
+    }
+}
@@ -1,10 +1,12 @@
 use crate::graph_paper::{
-    TextSetting,
     XSCALE_TEXT_SETTING,
     YSCALE_TEXT_SETTING
 };
 
-use super::{GraphPaper, Vec2};
+use crate::error::Error;
+use crate::render::{Element, TextSetting};
+
+use super::{AxisLayout, GraphPaper, Vec2, directed, validate_split};
 
 fn generate_ticks<F, G>(
     graph_paper    : &GraphPaper,
@@ -14,12 +16,12 @@ fn generate_ticks<F, G>(
     max_value      : f32,
     text_setting   : TextSetting,
     calc_tick_endpoint :G
-) -> Vec<String>
+) -> Vec<Element>
     where F: Fn(f32) -> Vec2, G: Fn(Vec2, f32) -> Vec2
 {
     let total_split = great_split * short_split;
     (0..(total_split + 1))
-        .map(|i| {
+        .flat_map(|i| {
             let value = (i as f32 / total_split as f32) * max_value;
             let from = calc_tick_from(value);
             let scale_length:f32;
@@ -29,27 +31,53 @@ fn generate_ticks<F, G>(
                 let line = graph_paper.get_line(from, to);
                 let text = GraphPaper::get_text(
                     from, value.to_string(),
-                    Some(text_setting.serialise())
+                    &text_setting
                 );
-                format!("{}\n\t{}", line, text)
+                vec![line, text]
             } else {
                 scale_length = graph_paper.short_split_length;
                 let to = calc_tick_endpoint(from, scale_length);
-                graph_paper.get_line(from, to)
+                vec![graph_paper.get_line(from, to)]
             }
         })
-        .collect::<Vec<String>>()
+        .collect::<Vec<Element>>()
+}
+
+/// `generate_ticks`と同じ目盛の値
+fn layout(great_split:u32, short_split:u32, max_value:f32, reversed:bool) -> AxisLayout {
+    let total_split = great_split * short_split;
+    let (greats, shorts) = (0..(total_split + 1))
+        .partition::<Vec<u32>, _>(|i| i % great_split == 0);
+    let value = |i:u32| (i as f32 / total_split as f32) * max_value;
+    AxisLayout {
+        log_base: None,
+        range: (0_f32, max_value),
+        reversed,
+        great_ticks: greats.into_iter().map(value).collect(),
+        short_ticks: shorts.into_iter().map(value).collect()
+    }
+}
+
+fn validate_max_value(max_value:f32) -> Result<(), Error> {
+    if !max_value.is_finite() || max_value <= 0_f32 {
+        return Err(Error::NonPositiveMaxValue(max_value));
+    }
+    Ok(())
 }
 
 /// X軸のリニア軸
+#[derive(Clone)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize))]
 pub struct XLinearScale {
     // 横長目盛分割数 / 横長目盛の短目盛での分割数
     pub h_great_split:u32,
     pub h_short_split:u32,
     pub max_value    :f32,
+    // 右から左へ値が増える
+    pub reversed     :bool,
 }
 impl super::XScale for XLinearScale {
-    fn get_h_splitten(&self, graph_paper:&GraphPaper) -> Vec<String> {
+    fn get_h_splitten(&self, graph_paper:&GraphPaper) -> Vec<Element> {
         let to_scaled = self.to_scaled_x(graph_paper);
         generate_ticks(
             graph_paper,
@@ -71,19 +99,37 @@ impl super::XScale for XLinearScale {
     fn to_scaled_x<'a>(&'a self, graph_paper:&'a GraphPaper) -> Box<dyn Fn(f32) -> f32 + 'a> {
         Box::new(|x:f32| -> f32 {
             let size = graph_paper.size.x - 2_f32 * graph_paper.margin;
-            graph_paper.margin + (x / self.max_value) * size
+            graph_paper.margin + directed(x / self.max_value, self.reversed) * size
         })
     }
+    fn get_x_range(&self) -> (f32, f32) {
+        (0_f32, self.max_value)
+    }
+    fn layout(&self) -> Option<AxisLayout> {
+        Some(layout(self.h_great_split, self.h_short_split, self.max_value, self.reversed))
+    }
+    fn validate(&self) -> Result<(), Error> {
+        validate_split(self.h_great_split, self.h_short_split)?;
+        validate_max_value(self.max_value)
+    }
+    #[cfg(feature = "spec")]
+    fn spec(&self) -> Option<crate::spec::XScaleSpec> {
+        Some(crate::spec::XScaleSpec::Linear(self.clone()))
+    }
 }
 /// Y軸のリニア軸
+#[derive(Clone)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize))]
 pub struct YLinearScale {
     // 縦長目盛分割数 / 縦長目盛の短目盛での分割数
     pub v_great_split :u32,
     pub v_short_split :u32,
     pub max_value     :f32,
+    // 上から下へ値が増える
+    pub reversed      :bool,
 }
 impl super::YScale for YLinearScale {
-    fn get_v_splitten(&self, graph_paper:&GraphPaper) -> Vec<String> {
+    fn get_v_splitten(&self, graph_paper:&GraphPaper) -> Vec<Element> {
         let to_scaled = self.to_scaled_y(graph_paper);
         generate_ticks(
             graph_paper,
@@ -105,7 +151,21 @@ impl super::YScale for YLinearScale {
     fn to_scaled_y<'a>(&'a self, graph_paper:&'a GraphPaper) -> Box<dyn Fn(f32) -> f32 + 'a> {
         Box::new(|y:f32| -> f32 {
             let size = graph_paper.size.y - 2_f32 * graph_paper.margin;
-            graph_paper.size.y - graph_paper.margin - (y / self.max_value) * size
+            graph_paper.size.y - graph_paper.margin - directed(y / self.max_value, self.reversed) * size
         })
     }
+    fn get_y_range(&self) -> (f32, f32) {
+        (0_f32, self.max_value)
+    }
+    fn layout(&self) -> Option<AxisLayout> {
+        Some(layout(self.v_great_split, self.v_short_split, self.max_value, self.reversed))
+    }
+    fn validate(&self) -> Result<(), Error> {
+        validate_split(self.v_great_split, self.v_short_split)?;
+        validate_max_value(self.max_value)
+    }
+    #[cfg(feature = "spec")]
+    fn spec(&self) -> Option<crate::spec::YScaleSpec> {
+        Some(crate::spec::YScaleSpec::Linear(self.clone()))
+    }
 }
\ No newline at end of file
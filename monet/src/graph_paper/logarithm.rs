@@ -1,8 +1,11 @@
-use crate::graph_paper::TextSetting;
+use crate::error::Error;
+use crate::render::{Element, TextSetting};
 
 use super::{
     XScale, YScale,
+    AxisLayout,
     GraphPaper, Vec2,
+    directed,
     XSCALE_TEXT_SETTING,
     YSCALE_TEXT_SETTING,
 };
@@ -13,7 +16,7 @@ fn get_subscale<F, G>(
     graph_paper:&GraphPaper,
     calc_tick_from: F,
     calc_tick_to: G
-) -> Vec<String>
+) -> Vec<Element>
     where F: Fn(f32) -> Vec2, G: Fn(Vec2, f32) -> Vec2
 {
     (2..base as u32)
@@ -22,7 +25,7 @@ fn get_subscale<F, G>(
             let from = calc_tick_from(value);
             let to = calc_tick_to(from, graph_paper.short_split_length);
             graph_paper.get_line(from, to)
-        }).collect::<Vec<String>>()
+        }).collect::<Vec<Element>>()
 }
 
 fn generate_ticks<F, G>(
@@ -33,11 +36,11 @@ fn generate_ticks<F, G>(
     calc_tick_from: F,
     calc_tick_to: G,
     text_setting: &TextSetting,
-) -> Vec<String>
+) -> Vec<Element>
     where F: Fn(f32) -> Vec2, G: Fn(Vec2, f32) -> Vec2
 {
     (from..to+1)
-        .flat_map(|i:i32| -> Vec<String> {
+        .flat_map(|i:i32| -> Vec<Element> {
             let value = base.powi(i);
             let from = calc_tick_from(value);
             let to = calc_tick_to(from, graph_paper.great_split_length);
@@ -46,7 +49,7 @@ fn generate_ticks<F, G>(
                 GraphPaper::get_text(
                     from,
                     value.to_string(),
-                    Some(text_setting.serialise())
+                    text_setting
                 )
             ];
             res.append(&mut get_subscale(
@@ -58,26 +61,54 @@ fn generate_ticks<F, G>(
             ));
             res
         })
-        .collect::<Vec<String>>()
+        .collect::<Vec<Element>>()
 }
 
-fn to_value(graph_paper: &GraphPaper, base: f32, from: i32, to: i32, size: f32) -> Box<dyn Fn(f32) -> f32 + '_> {
+fn to_value(graph_paper: &GraphPaper, base: f32, from: i32, to: i32, reversed: bool, size: f32) -> Box<dyn Fn(f32) -> f32 + '_> {
     Box::new(move |p:f32| -> f32 {
-        graph_paper.margin + (p.log(base) - from as f32) / (to - from) as f32  * size
+        graph_paper.margin + directed((p.log(base) - from as f32) / (to - from) as f32, reversed) * size
     })
 }
 
+/// `generate_ticks`と同じ目盛の値。範囲の外へ出る短目盛は除く
+fn layout(base: f32, from: i32, to: i32, reversed: bool) -> AxisLayout {
+    let range = (base.powi(from), base.powi(to));
+    AxisLayout {
+        log_base: Some(base),
+        range,
+        reversed,
+        great_ticks: (from..to+1).map(|i| base.powi(i)).collect(),
+        short_ticks: (from..to+1)
+            .flat_map(|i| (2..base as u32).map(move |j| j as f32 * base.powi(i)))
+            .filter(|v| *v <= range.1)
+            .collect()
+    }
+}
+
+fn validate(base: f32, from: i32, to: i32) -> Result<(), Error> {
+    if !base.is_finite() || base <= 1_f32 {
+        return Err(Error::InvalidLogBase(base));
+    }
+    if from >= to {
+        return Err(Error::EmptyRange { from: from as f32, to: to as f32 });
+    }
+    Ok(())
+}
+
 /// X軸の対数軸
 #[derive(Clone)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize))]
 pub struct XLogScale {
     pub base: f32,
     pub from: i32,
     pub to  : i32,
-    pub tick: u32
+    pub tick: u32,
+    // 右から左へ値が増える
+    pub reversed: bool,
 }
 
 impl XScale for XLogScale {
-    fn get_h_splitten(&self, graph_paper:&GraphPaper) -> Vec<String> {
+    fn get_h_splitten(&self, graph_paper:&GraphPaper) -> Vec<Element> {
         let x = self.to_scaled_x(graph_paper);
         generate_ticks(
             self.from,
@@ -100,21 +131,38 @@ impl XScale for XLogScale {
             self.base,
             self.from,
             self.to,
+            self.reversed,
             graph_paper.size.x - 2_f32 * graph_paper.margin
         )
     }
+    fn get_x_range(&self) -> (f32, f32) {
+        (self.base.powi(self.from), self.base.powi(self.to))
+    }
+    fn layout(&self) -> Option<AxisLayout> {
+        Some(layout(self.base, self.from, self.to, self.reversed))
+    }
+    fn validate(&self) -> Result<(), Error> {
+        validate(self.base, self.from, self.to)
+    }
+    #[cfg(feature = "spec")]
+    fn spec(&self) -> Option<crate::spec::XScaleSpec> {
+        Some(crate::spec::XScaleSpec::Log(self.clone()))
+    }
 }
 
 /// Y軸の対数軸
 #[derive(Clone)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize))]
 pub struct YLogScale {
     pub base: f32,
     pub from: i32,
     pub to  : i32,
+    // 上から下へ値が増える
+    pub reversed: bool,
 }
 
 impl YScale for YLogScale {
-    fn get_v_splitten(&self, graph_paper:&GraphPaper) -> Vec<String> {
+    fn get_v_splitten(&self, graph_paper:&GraphPaper) -> Vec<Element> {
         let y = self.to_scaled_y(graph_paper);
         generate_ticks(
             self.from,
@@ -123,7 +171,7 @@ impl YScale for YLogScale {
             self.base,
             |i:f32| Vec2 {
                 x: graph_paper.margin,
-                y: graph_paper.size.y - y(i)
+                y: y(i)
             },
             |from, scale_length:f32| {
                 from - Vec2::vec2(-scale_length, 0_f32)
@@ -138,8 +186,22 @@ impl YScale for YLogScale {
                 self.base,
                 self.from,
                 self.to,
+                self.reversed,
                 graph_paper.size.y - 2_f32 * graph_paper.margin
             )(f)
         })
     }
+    fn get_y_range(&self) -> (f32, f32) {
+        (self.base.powi(self.from), self.base.powi(self.to))
+    }
+    fn layout(&self) -> Option<AxisLayout> {
+        Some(layout(self.base, self.from, self.to, self.reversed))
+    }
+    fn validate(&self) -> Result<(), Error> {
+        validate(self.base, self.from, self.to)
+    }
+    #[cfg(feature = "spec")]
+    fn spec(&self) -> Option<crate::spec::YScaleSpec> {
+        Some(crate::spec::YScaleSpec::Log(self.clone()))
+    }
 }
\ No newline at end of file
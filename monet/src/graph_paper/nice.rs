@@ -0,0 +1,93 @@
+use crate::math::ticks::{
+    nice_step,
+    multiples_in,
+    format_tick,
+    transformed_ticks
+};
+use crate::render::{Element, TextSetting};
+
+use super::{GraphPaper, Vec2};
+
+/// `forward`で変換した単位できりの良い値に目盛を振り、`inverse`で戻した位置に置く
+#[allow(clippy::too_many_arguments)]
+pub(super) fn generate_ticks<F, G>(
+    graph_paper : &GraphPaper,
+    range       : (f32, f32),
+    forward     : &dyn Fn(f32) -> f32,
+    inverse     : &dyn Fn(f32) -> f32,
+    great_split : u32,
+    short_split : u32,
+    calc_tick_from     : F,
+    calc_tick_endpoint : G,
+    text_setting: &TextSetting,
+) -> Vec<Element>
+    where F: Fn(f32) -> Vec2, G: Fn(Vec2, f32) -> Vec2
+{
+    // 変換後の単位での範囲。変換が減少関数でも良いように並べ替える
+    let (a, b) = (forward(range.0), forward(range.1));
+    let (lo, hi) = (a.min(b), a.max(b));
+    let great_step = nice_step(lo, hi, great_split);
+    let short_step = great_step / short_split.max(1) as f32;
+    multiples_in(lo, hi, short_step)
+        .into_iter()
+        .flat_map(|value| {
+            let from = calc_tick_from(inverse(value));
+            if !(from.x.is_finite() && from.y.is_finite()) {
+                return Vec::new();
+            }
+            let is_great = ((value / great_step).round() * great_step - value).abs()
+                < short_step * 1e-3;
+            if is_great {
+                let to = calc_tick_endpoint(from, graph_paper.great_split_length);
+                let line = graph_paper.get_line(from, to);
+                let text = GraphPaper::get_text(
+                    from,
+                    format_tick(value, great_step),
+                    text_setting
+                );
+                vec![line, text]
+            } else {
+                let to = calc_tick_endpoint(from, graph_paper.short_split_length);
+                vec![graph_paper.get_line(from, to)]
+            }
+        })
+        .collect::<Vec<Element>>()
+}
+
+/// `forward`で変換した単位でほぼ等間隔に、元の単位のきりの良い値に目盛を振る
+#[allow(clippy::too_many_arguments)]
+pub(super) fn generate_transformed_ticks<F, G>(
+    graph_paper : &GraphPaper,
+    range       : (f32, f32),
+    forward     : &dyn Fn(f32) -> f32,
+    inverse     : &dyn Fn(f32) -> f32,
+    great_split : u32,
+    short_split : u32,
+    calc_tick_from     : F,
+    calc_tick_endpoint : G,
+    text_setting: &TextSetting,
+) -> Vec<Element>
+    where F: Fn(f32) -> Vec2, G: Fn(Vec2, f32) -> Vec2
+{
+    transformed_ticks(range, forward, inverse, great_split, short_split)
+        .into_iter()
+        .flat_map(|(value, great_step)| {
+            let from = calc_tick_from(value);
+            if !(from.x.is_finite() && from.y.is_finite()) {
+                return Vec::new();
+            }
+            match great_step {
+                Some(step) => {
+                    let to = calc_tick_endpoint(from, graph_paper.great_split_length);
+                    let line = graph_paper.get_line(from, to);
+                    let text = GraphPaper::get_text(from, format_tick(value, step), text_setting);
+                    vec![line, text]
+                },
+                None => {
+                    let to = calc_tick_endpoint(from, graph_paper.short_split_length);
+                    vec![graph_paper.get_line(from, to)]
+                }
+            }
+        })
+        .collect::<Vec<Element>>()
+}
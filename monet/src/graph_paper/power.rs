@@ -0,0 +1,140 @@
+use crate::error::Error;
+use crate::render::Element;
+
+use super::nice::generate_transformed_ticks;
+use super::function::{to_value, validate_function};
+
+use super::{
+    XScale, YScale,
+    GraphPaper, Vec2,
+    validate_split,
+    XSCALE_TEXT_SETTING,
+    YSCALE_TEXT_SETTING,
+};
+
+/// 符号を保ったまま`exponent`乗する
+fn power(exponent:f32) -> impl Fn(f32) -> f32 {
+    move |p:f32| p.abs().powf(exponent).copysign(p)
+}
+
+fn validate(exponent:f32, min_value:f32, max_value:f32) -> Result<(), Error> {
+    if !exponent.is_finite() || exponent <= 0_f32 {
+        return Err(Error::InvalidParameter { name: "exponent", value: exponent });
+    }
+    validate_function(power(exponent), min_value, max_value)
+}
+
+/// X軸の冪乗軸 (`exponent`が0.5なら平方根軸)
+#[derive(Clone)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize))]
+pub struct XPowerScale {
+    pub exponent: f32,
+    // 軸の最小値 / 最大値
+    pub min_value: f32,
+    pub max_value: f32,
+    // 長目盛のおおよその分割数 / 長目盛の短目盛での分割数
+    pub h_great_split: u32,
+    pub h_short_split: u32,
+    // 右から左へ値が増える
+    pub reversed: bool,
+}
+
+impl XScale for XPowerScale {
+    fn get_h_splitten(&self, graph_paper:&GraphPaper) -> Vec<Element> {
+        let x = self.to_scaled_x(graph_paper);
+        generate_transformed_ticks(
+            graph_paper,
+            self.get_x_range(),
+            &power(self.exponent),
+            &power(1_f32 / self.exponent),
+            self.h_great_split,
+            self.h_short_split,
+            |i:f32| Vec2 {
+                x: x(i),
+                y: graph_paper.size.y - graph_paper.margin
+            },
+            |from, scale_length:f32| {
+                from - Vec2::vec2(0_f32, scale_length)
+            },
+            &XSCALE_TEXT_SETTING
+        )
+    }
+    fn to_scaled_x<'a>(&'a self, graph_paper:&'a GraphPaper) -> Box<dyn Fn(f32) -> f32 + 'a> {
+        to_value(
+            graph_paper,
+            power(self.exponent),
+            self.get_x_range(),
+            self.reversed,
+            graph_paper.size.x - 2_f32 * graph_paper.margin
+        )
+    }
+    fn get_x_range(&self) -> (f32, f32) {
+        (self.min_value, self.max_value)
+    }
+    fn validate(&self) -> Result<(), Error> {
+        validate_split(self.h_great_split, self.h_short_split)?;
+        validate(self.exponent, self.min_value, self.max_value)
+    }
+    #[cfg(feature = "spec")]
+    fn spec(&self) -> Option<crate::spec::XScaleSpec> {
+        Some(crate::spec::XScaleSpec::Power(self.clone()))
+    }
+}
+
+/// Y軸の冪乗軸 (`exponent`が0.5なら平方根軸)
+#[derive(Clone)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize))]
+pub struct YPowerScale {
+    pub exponent: f32,
+    // 軸の最小値 / 最大値
+    pub min_value: f32,
+    pub max_value: f32,
+    // 長目盛のおおよその分割数 / 長目盛の短目盛での分割数
+    pub v_great_split: u32,
+    pub v_short_split: u32,
+    // 上から下へ値が増える
+    pub reversed: bool,
+}
+
+impl YScale for YPowerScale {
+    fn get_v_splitten(&self, graph_paper:&GraphPaper) -> Vec<Element> {
+        let y = self.to_scaled_y(graph_paper);
+        generate_transformed_ticks(
+            graph_paper,
+            self.get_y_range(),
+            &power(self.exponent),
+            &power(1_f32 / self.exponent),
+            self.v_great_split,
+            self.v_short_split,
+            |i:f32| Vec2 {
+                x: graph_paper.margin,
+                y: y(i)
+            },
+            |from, scale_length:f32| {
+                from + Vec2::vec2(scale_length, 0_f32)
+            },
+            &YSCALE_TEXT_SETTING
+        )
+    }
+    fn to_scaled_y<'a>(&'a self, graph_paper:&'a GraphPaper) -> Box<dyn Fn(f32) -> f32 + 'a> {
+        let to_value = to_value(
+            graph_paper,
+            power(self.exponent),
+            self.get_y_range(),
+            self.reversed,
+            graph_paper.size.y - 2_f32 * graph_paper.margin
+        );
+        Box::new(move |f:f32| graph_paper.size.y - to_value(f))
+    }
+    fn get_y_range(&self) -> (f32, f32) {
+        (self.min_value, self.max_value)
+    }
+    fn validate(&self) -> Result<(), Error> {
+        validate_split(self.v_great_split, self.v_short_split)?;
+        validate(self.exponent, self.min_value, self.max_value)
+    }
+    #[cfg(feature = "spec")]
+    fn spec(&self) -> Option<crate::spec::YScaleSpec> {
+        Some(crate::spec::YScaleSpec::Power(self.clone()))
+    }
+}
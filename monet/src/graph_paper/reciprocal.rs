@@ -0,0 +1,179 @@
+use crate::error::Error;
+use crate::render::Element;
+
+use super::nice::generate_ticks;
+
+use super::{
+    XScale, YScale,
+    GraphPaper, Vec2,
+    directed,
+    validate_split,
+    validate_range,
+    XSCALE_TEXT_SETTING,
+    YSCALE_TEXT_SETTING,
+};
+
+/// 目盛をきりの良い値にする領域
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum TickDomain {
+    // 元の値 (例: T)
+    Original,
+    // 逆数 (例: 1000/T)
+    Reciprocal,
+}
+
+fn to_value(
+    graph_paper: &GraphPaper,
+    factor: f32,
+    range: (f32, f32),
+    reversed: bool,
+    size: f32
+) -> Box<dyn Fn(f32) -> f32 + '_> {
+    // 逆数が小さい側を始点にする
+    let (a, b) = (factor / range.0, factor / range.1);
+    let (lo, hi) = (a.min(b), a.max(b));
+    Box::new(move |p:f32| -> f32 {
+        graph_paper.margin + directed((factor / p - lo) / (hi - lo), reversed) * size
+    })
+}
+
+fn validate(factor:f32, min_value:f32, max_value:f32) -> Result<(), Error> {
+    if !factor.is_finite() || factor == 0_f32 {
+        return Err(Error::InvalidParameter { name: "factor", value: factor });
+    }
+    validate_range(min_value, max_value)?;
+    if min_value <= 0_f32 && 0_f32 <= max_value {
+        return Err(Error::RangeIncludesZero { from: min_value, to: max_value });
+    }
+    Ok(())
+}
+
+fn tick_conversion(tick_domain:TickDomain, factor:f32) -> Box<dyn Fn(f32) -> f32> {
+    match tick_domain {
+        TickDomain::Original => Box::new(|v:f32| v),
+        TickDomain::Reciprocal => Box::new(move |v:f32| factor / v),
+    }
+}
+
+/// X軸の逆数軸。`factor / 値`に比例した位置に点を置く
+#[derive(Clone)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize))]
+pub struct XReciprocalScale {
+    // 元の値での軸の範囲
+    pub min_value: f32,
+    pub max_value: f32,
+    // 逆数に掛ける係数 (1000/T なら 1000)
+    pub factor: f32,
+    pub tick_domain: TickDomain,
+    // 長目盛のおおよその分割数 / 長目盛の短目盛での分割数
+    pub h_great_split: u32,
+    pub h_short_split: u32,
+    // 右から左へ逆数が増える
+    pub reversed: bool,
+}
+
+impl XScale for XReciprocalScale {
+    fn get_h_splitten(&self, graph_paper:&GraphPaper) -> Vec<Element> {
+        let x = self.to_scaled_x(graph_paper);
+        // 逆数は自身の逆関数なので、変換と逆変換は同じ関数になる
+        let conversion = tick_conversion(self.tick_domain, self.factor);
+        generate_ticks(
+            graph_paper,
+            self.get_x_range(),
+            &conversion,
+            &conversion,
+            self.h_great_split,
+            self.h_short_split,
+            |i:f32| Vec2 {
+                x: x(i),
+                y: graph_paper.size.y - graph_paper.margin
+            },
+            |from, scale_length:f32| {
+                from - Vec2::vec2(0_f32, scale_length)
+            },
+            &XSCALE_TEXT_SETTING
+        )
+    }
+    fn to_scaled_x<'a>(&'a self, graph_paper:&'a GraphPaper) -> Box<dyn Fn(f32) -> f32 + 'a> {
+        to_value(
+            graph_paper,
+            self.factor,
+            self.get_x_range(),
+            self.reversed,
+            graph_paper.size.x - 2_f32 * graph_paper.margin
+        )
+    }
+    fn get_x_range(&self) -> (f32, f32) {
+        (self.min_value, self.max_value)
+    }
+    fn validate(&self) -> Result<(), Error> {
+        validate_split(self.h_great_split, self.h_short_split)?;
+        validate(self.factor, self.min_value, self.max_value)
+    }
+    #[cfg(feature = "spec")]
+    fn spec(&self) -> Option<crate::spec::XScaleSpec> {
+        Some(crate::spec::XScaleSpec::Reciprocal(self.clone()))
+    }
+}
+
+/// Y軸の逆数軸。`factor / 値`に比例した位置に点を置く
+#[derive(Clone)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize))]
+pub struct YReciprocalScale {
+    // 元の値での軸の範囲
+    pub min_value: f32,
+    pub max_value: f32,
+    // 逆数に掛ける係数 (1000/T なら 1000)
+    pub factor: f32,
+    pub tick_domain: TickDomain,
+    // 長目盛のおおよその分割数 / 長目盛の短目盛での分割数
+    pub v_great_split: u32,
+    pub v_short_split: u32,
+    // 上から下へ逆数が増える
+    pub reversed: bool,
+}
+
+impl YScale for YReciprocalScale {
+    fn get_v_splitten(&self, graph_paper:&GraphPaper) -> Vec<Element> {
+        let y = self.to_scaled_y(graph_paper);
+        let conversion = tick_conversion(self.tick_domain, self.factor);
+        generate_ticks(
+            graph_paper,
+            self.get_y_range(),
+            &conversion,
+            &conversion,
+            self.v_great_split,
+            self.v_short_split,
+            |i:f32| Vec2 {
+                x: graph_paper.margin,
+                y: y(i)
+            },
+            |from, scale_length:f32| {
+                from + Vec2::vec2(scale_length, 0_f32)
+            },
+            &YSCALE_TEXT_SETTING
+        )
+    }
+    fn to_scaled_y<'a>(&'a self, graph_paper:&'a GraphPaper) -> Box<dyn Fn(f32) -> f32 + 'a> {
+        let to_value = to_value(
+            graph_paper,
+            self.factor,
+            self.get_y_range(),
+            self.reversed,
+            graph_paper.size.y - 2_f32 * graph_paper.margin
+        );
+        Box::new(move |f:f32| graph_paper.size.y - to_value(f))
+    }
+    fn get_y_range(&self) -> (f32, f32) {
+        (self.min_value, self.max_value)
+    }
+    fn validate(&self) -> Result<(), Error> {
+        validate_split(self.v_great_split, self.v_short_split)?;
+        validate(self.factor, self.min_value, self.max_value)
+    }
+    #[cfg(feature = "spec")]
+    fn spec(&self) -> Option<crate::spec::YScaleSpec> {
+        Some(crate::spec::YScaleSpec::Reciprocal(self.clone()))
+    }
+}
@@ -0,0 +1,208 @@
+use crate::error::Error;
+use crate::render::Element;
+
+use super::nice::generate_ticks;
+
+use super::{
+    XScale, YScale,
+    AxisLayout,
+    GraphPaper, Vec2,
+    validate_split,
+    XSECONDARY_TEXT_SETTING,
+    YSECONDARY_TEXT_SETTING,
+};
+
+/// 副軸の目盛を振る主軸の範囲。変換すると無限大になる端 (1240/nmの0など) は長目盛1つ分だけ内側へ寄せる
+fn tick_range(range:(f32, f32), forward:&dyn Fn(f32) -> f32, great_split:u32) -> Result<(f32, f32), Error> {
+    let (from, to) = range;
+    let inward = (to - from) / great_split.max(1) as f32;
+    let clamp = |end:f32, inward:f32| {
+        [end, end + inward].into_iter()
+            .find(|v| forward(*v).is_finite())
+            .ok_or(Error::InvalidParameter { name: "forward", value: end })
+    };
+    Ok((clamp(from, inward)?, clamp(to, -inward)?))
+}
+
+/// 主軸を変換した単位で上側に目盛を振るX軸 (例: ℃ ↔ ℉)
+pub struct XSecondaryScale {
+    // 元になる軸。プロット点の位置はこの軸で決まる
+    pub primary: Box<dyn XScale>,
+    // 主軸の値 → 副軸の値 / 副軸の値 → 主軸の値
+    pub forward: Box<dyn Fn(f32) -> f32>,
+    pub inverse: Box<dyn Fn(f32) -> f32>,
+    // 長目盛のおおよその分割数 / 長目盛の短目盛での分割数
+    pub great_split: u32,
+    pub short_split: u32,
+}
+impl XScale for XSecondaryScale {
+    fn get_h_splitten(&self, graph_paper:&GraphPaper) -> Vec<Element> {
+        let x = self.primary.to_scaled_x(graph_paper);
+        let mut res = self.primary.get_h_splitten(graph_paper);
+        // 変換できない範囲は`validate`で弾いている
+        let Ok(range) = tick_range(self.primary.get_x_range(), &self.forward, self.great_split) else {
+            return res;
+        };
+        res.extend(generate_ticks(
+            graph_paper,
+            range,
+            &self.forward,
+            &self.inverse,
+            self.great_split,
+            self.short_split,
+            |v:f32| Vec2::vec2(x(v), graph_paper.margin),
+            |from:Vec2, scale_length:f32| {
+                from + Vec2::vec2(0_f32, scale_length)
+            },
+            &XSECONDARY_TEXT_SETTING
+        ));
+        res
+    }
+    fn to_scaled_x<'a>(&'a self, graph_paper:&'a GraphPaper) -> Box<dyn Fn(f32) -> f32 + 'a> {
+        self.primary.to_scaled_x(graph_paper)
+    }
+    fn get_x_range(&self) -> (f32, f32) {
+        self.primary.get_x_range()
+    }
+    fn format_x(&self, value:f32) -> String {
+        self.primary.format_x(value)
+    }
+    fn frame_gaps(&self, graph_paper:&GraphPaper) -> Vec<(f32, f32)> {
+        self.primary.frame_gaps(graph_paper)
+    }
+    /// 主軸の配置。副軸の目盛は含まない
+    fn layout(&self) -> Option<AxisLayout> {
+        self.primary.layout()
+    }
+    fn validate(&self) -> Result<(), Error> {
+        validate_split(self.great_split, self.short_split)?;
+        self.primary.validate()?;
+        tick_range(self.primary.get_x_range(), &self.forward, self.great_split).map(|_| ())
+    }
+    /// 主軸の設定。変換の関数は書けないので副軸は省く
+    #[cfg(feature = "spec")]
+    fn spec(&self) -> Option<crate::spec::XScaleSpec> {
+        self.primary.spec()
+    }
+}
+
+/// 主軸を変換した単位で右側に目盛を振るY軸
+pub struct YSecondaryScale {
+    // 元になる軸。プロット点の位置はこの軸で決まる
+    pub primary: Box<dyn YScale>,
+    // 主軸の値 → 副軸の値 / 副軸の値 → 主軸の値
+    pub forward: Box<dyn Fn(f32) -> f32>,
+    pub inverse: Box<dyn Fn(f32) -> f32>,
+    // 長目盛のおおよその分割数 / 長目盛の短目盛での分割数
+    pub great_split: u32,
+    pub short_split: u32,
+}
+impl YScale for YSecondaryScale {
+    fn get_v_splitten(&self, graph_paper:&GraphPaper) -> Vec<Element> {
+        let y = self.primary.to_scaled_y(graph_paper);
+        let mut res = self.primary.get_v_splitten(graph_paper);
+        let Ok(range) = tick_range(self.primary.get_y_range(), &self.forward, self.great_split) else {
+            return res;
+        };
+        res.extend(generate_ticks(
+            graph_paper,
+            range,
+            &self.forward,
+            &self.inverse,
+            self.great_split,
+            self.short_split,
+            |v:f32| Vec2::vec2(graph_paper.size.x - graph_paper.margin, y(v)),
+            |from:Vec2, scale_length:f32| {
+                from - Vec2::vec2(scale_length, 0_f32)
+            },
+            &YSECONDARY_TEXT_SETTING
+        ));
+        res
+    }
+    fn to_scaled_y<'a>(&'a self, graph_paper:&'a GraphPaper) -> Box<dyn Fn(f32) -> f32 + 'a> {
+        self.primary.to_scaled_y(graph_paper)
+    }
+    fn get_y_range(&self) -> (f32, f32) {
+        self.primary.get_y_range()
+    }
+    fn format_y(&self, value:f32) -> String {
+        self.primary.format_y(value)
+    }
+    fn frame_gaps(&self, graph_paper:&GraphPaper) -> Vec<(f32, f32)> {
+        self.primary.frame_gaps(graph_paper)
+    }
+    /// 主軸の配置。副軸の目盛は含まない
+    fn layout(&self) -> Option<AxisLayout> {
+        self.primary.layout()
+    }
+    fn validate(&self) -> Result<(), Error> {
+        validate_split(self.great_split, self.short_split)?;
+        self.primary.validate()?;
+        tick_range(self.primary.get_y_range(), &self.forward, self.great_split).map(|_| ())
+    }
+    /// 主軸の設定。変換の関数は書けないので副軸は省く
+    #[cfg(feature = "spec")]
+    fn spec(&self) -> Option<crate::spec::YScaleSpec> {
+        self.primary.spec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph_paper::{XLinearScale, YLinearScale};
+    use crate::render::Element;
+    use super::*;
+
+    fn graph_paper() -> GraphPaper {
+        GraphPaper {
+            name: String::new(),
+            margin: 100_f32,
+            size: Vec2::vec2(1100_f32, 1100_f32),
+            points: Vec::new(),
+            stroke_width: 1_f32,
+            great_split_length: 20_f32,
+            short_split_length: 10_f32
+        }
+    }
+
+    fn labels(elements:&[Element]) -> Vec<String> {
+        elements.iter()
+            .filter_map(|e| match e {
+                Element::Text(text) => Some(text.content()),
+                _ => None
+            })
+            .collect()
+    }
+
+    #[test]
+    fn electron_volts_over_wavelength() {
+        // 0から1000nmのリニア軸に、eV = 1240 / nm を振る
+        let scale = XSecondaryScale {
+            primary: Box::new(XLinearScale { h_great_split: 10, h_short_split: 2, max_value: 1000_f32, reversed: false }),
+            forward: Box::new(|nm:f32| 1240_f32 / nm),
+            inverse: Box::new(|ev:f32| 1240_f32 / ev),
+            great_split: 10,
+            short_split: 2
+        };
+        assert_eq!(scale.validate(), Ok(()));
+        let graph_paper = graph_paper();
+        let primary = labels(&scale.primary.get_h_splitten(&graph_paper));
+        let all = labels(&scale.get_h_splitten(&graph_paper));
+        let secondary = &all[primary.len()..];
+        // 100nm (12.4eV) から1000nm (1.24eV) までの目盛
+        assert!(secondary.len() >= 5, "{:?}", secondary);
+        assert!(secondary.contains(&"2".to_string()) && secondary.contains(&"12".to_string()), "{:?}", secondary);
+    }
+
+    #[test]
+    fn unconvertible_range_is_an_error() {
+        let scale = YSecondaryScale {
+            primary: Box::new(YLinearScale { v_great_split: 1, v_short_split: 1, max_value: 1_f32, reversed: false }),
+            forward: Box::new(|v:f32| 1_f32 / (v * (v - 1_f32))),
+            inverse: Box::new(|v:f32| v),
+            great_split: 1,
+            short_split: 1
+        };
+        assert!(matches!(scale.validate(), Err(Error::InvalidParameter { name: "forward", .. })));
+    }
+}
@@ -0,0 +1,201 @@
+use crate::error::Error;
+use crate::render::{Element, TextSetting};
+
+use super::{
+    XScale, YScale,
+    GraphPaper, Vec2,
+    directed,
+    validate_range,
+    XSCALE_TEXT_SETTING,
+    YSCALE_TEXT_SETTING,
+};
+
+/// 閾値の内側は線形、外側は対数となる変換。0を跨いで符号付きで連続する
+fn transform(p:f32, base:f32, threshold:f32) -> f32 {
+    let magnitude = p.abs();
+    let scaled = if magnitude <= threshold {
+        magnitude / threshold
+    } else {
+        1_f32 + (magnitude / threshold).log(base)
+    };
+    scaled.copysign(p)
+}
+
+fn to_value(
+    graph_paper: &GraphPaper,
+    base: f32,
+    threshold: f32,
+    range: (f32, f32),
+    reversed: bool,
+    size: f32
+) -> Box<dyn Fn(f32) -> f32 + '_> {
+    let lo = transform(range.0, base, threshold);
+    let hi = transform(range.1, base, threshold);
+    Box::new(move |p:f32| -> f32 {
+        let ratio = (transform(p, base, threshold) - lo) / (hi - lo);
+        graph_paper.margin + directed(ratio, reversed) * size
+    })
+}
+
+/// 0と、閾値以上の冪を正負対称に列挙する。(値, 長目盛か)
+fn tick_values(base:f32, threshold:f32, range:(f32, f32)) -> Vec<(f32, bool)> {
+    let (lo, hi) = (range.0.min(range.1), range.1.max(range.0));
+    let in_range = |v:f32| lo <= v && v <= hi;
+    let mut values = vec![(0_f32, true)];
+    let first = threshold.log(base).ceil() as i32;
+    let last = lo.abs().max(hi.abs()).log(base).floor() as i32;
+    for i in first..last + 1 {
+        let power = base.powi(i);
+        for sign in [1_f32, -1_f32] {
+            values.push((sign * power, true));
+            for j in 2..base as u32 {
+                values.push((sign * j as f32 * power, false));
+            }
+        }
+    }
+    values.into_iter().filter(|(v, _)| in_range(*v)).collect()
+}
+
+fn generate_ticks<F, G>(
+    graph_paper: &GraphPaper,
+    values: Vec<(f32, bool)>,
+    calc_tick_from: F,
+    calc_tick_to: G,
+    text_setting: &TextSetting,
+) -> Vec<Element>
+    where F: Fn(f32) -> Vec2, G: Fn(Vec2, f32) -> Vec2
+{
+    values.into_iter()
+        .flat_map(|(value, is_great)| {
+            let from = calc_tick_from(value);
+            if is_great {
+                let to = calc_tick_to(from, graph_paper.great_split_length);
+                let line = graph_paper.get_line(from, to);
+                let text = GraphPaper::get_text(
+                    from,
+                    value.to_string(),
+                    text_setting
+                );
+                vec![line, text]
+            } else {
+                let to = calc_tick_to(from, graph_paper.short_split_length);
+                vec![graph_paper.get_line(from, to)]
+            }
+        })
+        .collect::<Vec<Element>>()
+}
+
+fn validate(base:f32, threshold:f32, min_value:f32, max_value:f32) -> Result<(), Error> {
+    if !base.is_finite() || base <= 1_f32 {
+        return Err(Error::InvalidLogBase(base));
+    }
+    if !threshold.is_finite() || threshold <= 0_f32 {
+        return Err(Error::InvalidParameter { name: "threshold", value: threshold });
+    }
+    validate_range(min_value, max_value)
+}
+
+/// X軸の対称対数軸
+#[derive(Clone)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize))]
+pub struct XSymlogScale {
+    pub base: f32,
+    // 線形となる範囲 (-threshold..threshold)
+    pub threshold: f32,
+    // 軸の最小値 / 最大値
+    pub min_value: f32,
+    pub max_value: f32,
+    // 右から左へ値が増える
+    pub reversed: bool,
+}
+
+impl XScale for XSymlogScale {
+    fn get_h_splitten(&self, graph_paper:&GraphPaper) -> Vec<Element> {
+        let x = self.to_scaled_x(graph_paper);
+        generate_ticks(
+            graph_paper,
+            tick_values(self.base, self.threshold, self.get_x_range()),
+            |i:f32| Vec2 {
+                x: x(i),
+                y: graph_paper.size.y - graph_paper.margin
+            },
+            |from, scale_length:f32| {
+                from - Vec2::vec2(0_f32, scale_length)
+            },
+            &XSCALE_TEXT_SETTING
+        )
+    }
+    fn to_scaled_x<'a>(&'a self, graph_paper:&'a GraphPaper) -> Box<dyn Fn(f32) -> f32 + 'a> {
+        to_value(
+            graph_paper,
+            self.base,
+            self.threshold,
+            self.get_x_range(),
+            self.reversed,
+            graph_paper.size.x - 2_f32 * graph_paper.margin
+        )
+    }
+    fn get_x_range(&self) -> (f32, f32) {
+        (self.min_value, self.max_value)
+    }
+    fn validate(&self) -> Result<(), Error> {
+        validate(self.base, self.threshold, self.min_value, self.max_value)
+    }
+    #[cfg(feature = "spec")]
+    fn spec(&self) -> Option<crate::spec::XScaleSpec> {
+        Some(crate::spec::XScaleSpec::Symlog(self.clone()))
+    }
+}
+
+/// Y軸の対称対数軸
+#[derive(Clone)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize))]
+pub struct YSymlogScale {
+    pub base: f32,
+    // 線形となる範囲 (-threshold..threshold)
+    pub threshold: f32,
+    // 軸の最小値 / 最大値
+    pub min_value: f32,
+    pub max_value: f32,
+    // 上から下へ値が増える
+    pub reversed: bool,
+}
+
+impl YScale for YSymlogScale {
+    fn get_v_splitten(&self, graph_paper:&GraphPaper) -> Vec<Element> {
+        let y = self.to_scaled_y(graph_paper);
+        generate_ticks(
+            graph_paper,
+            tick_values(self.base, self.threshold, self.get_y_range()),
+            |i:f32| Vec2 {
+                x: graph_paper.margin,
+                y: y(i)
+            },
+            |from, scale_length:f32| {
+                from + Vec2::vec2(scale_length, 0_f32)
+            },
+            &YSCALE_TEXT_SETTING
+        )
+    }
+    fn to_scaled_y<'a>(&'a self, graph_paper:&'a GraphPaper) -> Box<dyn Fn(f32) -> f32 + 'a> {
+        let to_value = to_value(
+            graph_paper,
+            self.base,
+            self.threshold,
+            self.get_y_range(),
+            self.reversed,
+            graph_paper.size.y - 2_f32 * graph_paper.margin
+        );
+        Box::new(move |f:f32| graph_paper.size.y - to_value(f))
+    }
+    fn get_y_range(&self) -> (f32, f32) {
+        (self.min_value, self.max_value)
+    }
+    fn validate(&self) -> Result<(), Error> {
+        validate(self.base, self.threshold, self.min_value, self.max_value)
+    }
+    #[cfg(feature = "spec")]
+    fn spec(&self) -> Option<crate::spec::YScaleSpec> {
+        Some(crate::spec::YScaleSpec::Symlog(self.clone()))
+    }
+}
@@ -0,0 +1,178 @@
+use crate::error::Error;
+use crate::math::calendar::{calendar_ticks, format_timestamp, validate_calendar_ticks};
+use crate::render::{Element, TextSetting};
+
+use super::{
+    XScale, YScale,
+    GraphPaper, Vec2,
+    directed,
+    validate_split,
+    XSCALE_TEXT_SETTING,
+    YSCALE_TEXT_SETTING,
+};
+
+#[allow(clippy::too_many_arguments)]
+fn generate_ticks<F, G>(
+    graph_paper: &GraphPaper,
+    start: f64,
+    end: f64,
+    great_split: u32,
+    short_split: u32,
+    calc_tick_from: F,
+    calc_tick_to: G,
+    text_setting: &TextSetting,
+) -> Vec<Element>
+    where F: Fn(f32) -> Vec2, G: Fn(Vec2, f32) -> Vec2
+{
+    // 目盛の数は`validate`で確かめている
+    let Ok((greats, shorts)) = calendar_ticks(start, end, great_split, short_split) else {
+        return Vec::new();
+    };
+    let mut res = greats.into_iter()
+        .flat_map(|(epoch, label)| {
+            let from = calc_tick_from((epoch - start) as f32);
+            let to = calc_tick_to(from, graph_paper.great_split_length);
+            let line = graph_paper.get_line(from, to);
+            let text = GraphPaper::get_text(from, label, text_setting);
+            vec![line, text]
+        })
+        .collect::<Vec<Element>>();
+    res.extend(shorts.into_iter().map(|epoch| {
+        let from = calc_tick_from((epoch - start) as f32);
+        let to = calc_tick_to(from, graph_paper.short_split_length);
+        graph_paper.get_line(from, to)
+    }));
+    res
+}
+
+/// X軸の時刻軸。
+/// f32の精度落ちを避けるため、点の値は`start`からの経過秒数で与える
+#[derive(Clone)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize))]
+pub struct XTimeScale {
+    // 軸の始点 / 終点 (UNIX時刻, 秒)
+    pub start: f64,
+    pub end  : f64,
+    // 長目盛のおおよその数 / 長目盛の短目盛での分割数
+    pub h_great_split: u32,
+    pub h_short_split: u32,
+    // 右から左へ時刻が進む
+    pub reversed: bool,
+}
+impl XTimeScale {
+    /// UNIX時刻を点の値 (`start`からの経過秒数) にする
+    pub fn offset(&self, epoch:f64) -> f32 {
+        (epoch - self.start) as f32
+    }
+}
+
+impl XScale for XTimeScale {
+    fn get_h_splitten(&self, graph_paper:&GraphPaper) -> Vec<Element> {
+        let x = self.to_scaled_x(graph_paper);
+        generate_ticks(
+            graph_paper,
+            self.start,
+            self.end,
+            self.h_great_split,
+            self.h_short_split,
+            |i:f32| Vec2 {
+                x: x(i),
+                y: graph_paper.size.y - graph_paper.margin
+            },
+            |from, scale_length:f32| {
+                from - Vec2::vec2(0_f32, scale_length)
+            },
+            &XSCALE_TEXT_SETTING
+        )
+    }
+    fn to_scaled_x<'a>(&'a self, graph_paper:&'a GraphPaper) -> Box<dyn Fn(f32) -> f32 + 'a> {
+        Box::new(|x:f32| -> f32 {
+            let size = graph_paper.size.x - 2_f32 * graph_paper.margin;
+            let span = (self.end - self.start) as f32;
+            graph_paper.margin + directed(x / span, self.reversed) * size
+        })
+    }
+    fn get_x_range(&self) -> (f32, f32) {
+        (0_f32, (self.end - self.start) as f32)
+    }
+    fn format_x(&self, value:f32) -> String {
+        format_timestamp(self.start + value as f64)
+    }
+    fn validate(&self) -> Result<(), Error> {
+        validate_split(self.h_great_split, self.h_short_split)?;
+        if !(self.start.is_finite() && self.end.is_finite()) || self.start >= self.end {
+            return Err(Error::EmptyRange { from: self.start as f32, to: self.end as f32 });
+        }
+        validate_calendar_ticks(self.start, self.end, self.h_great_split, self.h_short_split)
+    }
+    #[cfg(feature = "spec")]
+    fn spec(&self) -> Option<crate::spec::XScaleSpec> {
+        Some(crate::spec::XScaleSpec::Time(self.clone()))
+    }
+}
+
+/// Y軸の時刻軸。
+/// f32の精度落ちを避けるため、点の値は`start`からの経過秒数で与える
+#[derive(Clone)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize))]
+pub struct YTimeScale {
+    // 軸の始点 / 終点 (UNIX時刻, 秒)
+    pub start: f64,
+    pub end  : f64,
+    // 長目盛のおおよその数 / 長目盛の短目盛での分割数
+    pub v_great_split: u32,
+    pub v_short_split: u32,
+    // 上から下へ時刻が進む
+    pub reversed: bool,
+}
+impl YTimeScale {
+    /// UNIX時刻を点の値 (`start`からの経過秒数) にする
+    pub fn offset(&self, epoch:f64) -> f32 {
+        (epoch - self.start) as f32
+    }
+}
+
+impl YScale for YTimeScale {
+    fn get_v_splitten(&self, graph_paper:&GraphPaper) -> Vec<Element> {
+        let y = self.to_scaled_y(graph_paper);
+        generate_ticks(
+            graph_paper,
+            self.start,
+            self.end,
+            self.v_great_split,
+            self.v_short_split,
+            |i:f32| Vec2 {
+                x: graph_paper.margin,
+                y: y(i)
+            },
+            |from, scale_length:f32| {
+                from + Vec2::vec2(scale_length, 0_f32)
+            },
+            &YSCALE_TEXT_SETTING
+        )
+    }
+    fn to_scaled_y<'a>(&'a self, graph_paper:&'a GraphPaper) -> Box<dyn Fn(f32) -> f32 + 'a> {
+        Box::new(|y:f32| -> f32 {
+            let size = graph_paper.size.y - 2_f32 * graph_paper.margin;
+            let span = (self.end - self.start) as f32;
+            graph_paper.size.y - graph_paper.margin - directed(y / span, self.reversed) * size
+        })
+    }
+    fn get_y_range(&self) -> (f32, f32) {
+        (0_f32, (self.end - self.start) as f32)
+    }
+    fn format_y(&self, value:f32) -> String {
+        format_timestamp(self.start + value as f64)
+    }
+    fn validate(&self) -> Result<(), Error> {
+        validate_split(self.v_great_split, self.v_short_split)?;
+        if !(self.start.is_finite() && self.end.is_finite()) || self.start >= self.end {
+            return Err(Error::EmptyRange { from: self.start as f32, to: self.end as f32 });
+        }
+        validate_calendar_ticks(self.start, self.end, self.v_great_split, self.v_short_split)
+    }
+    #[cfg(feature = "spec")]
+    fn spec(&self) -> Option<crate::spec::YScaleSpec> {
+        Some(crate::spec::YScaleSpec::Time(self.clone()))
+    }
+}
@@ -0,0 +1,243 @@
+use std::fmt;
+use std::io::{BufWriter, Write};
+
+use crate::error::Error;
+use crate::graph_paper::Graph;
+use crate::math::Vec2;
+use crate::svg::{Escaped, SvgBackend};
+
+/// 表示の見た目
+const STYLE:&str = "\
+body { margin: 0; font-family: sans-serif; }
+svg { display: block; width: 100%; height: auto; }
+#tooltip {
+\tposition: absolute; display: none; pointer-events: none; white-space: pre;
+\tpadding: 4px 8px; background: rgba(255, 255, 255, 0.9); border: 1px solid #888; font-size: 13px;
+}
+";
+
+/// 値の表示と、プロット領域の拡大 / 移動。
+/// `DATA`は [用紙のx, 用紙のy, 系列の名前, 値の説明, xの値, yの値] の並び、`AREA`はプロット領域 [x, y, 幅, 高さ]
+const SCRIPT:&str = r##"(() => {
+	const svg = document.querySelector("svg");
+	const tooltip = document.getElementById("tooltip");
+	const view = document.getElementById("plot-data");
+	const area = { x: AREA[0], y: AREA[1], width: AREA[2], height: AREA[3] };
+	// 拡大しても点の大きさと線の太さは変えない
+	const sizes = [...view.querySelectorAll("[stroke-width], #points circle")].map(e => [
+		e, e.getAttribute("stroke-width"), e.closest("#points") ? e.getAttribute("r") : null
+	]);
+	// 目盛は大きさを変えずに、値に合わせて軸の向きにだけ動かす
+	const ticks = ["x", "y"].flatMap(axis => [...document.querySelectorAll(`#${axis}-axis > *`)].map(e => {
+		const box = e.getBBox();
+		const centre = axis === "x" ? box.x + box.width / 2 : box.y + box.height / 2;
+		return [e, axis, centre, e.getAttribute("transform") ?? ""];
+	}));
+	let k = 1, tx = 0, ty = 0, drag = null;
+	const apply = () => {
+		view.setAttribute("transform", `translate(${tx} ${ty}) scale(${k})`);
+		sizes.forEach(([e, w, r]) => {
+			if (w !== null) e.setAttribute("stroke-width", w / k);
+			if (r !== null) e.setAttribute("r", r / k);
+		});
+		ticks.forEach(([e, axis, centre, transform]) => {
+			const [t, lo, length] = axis === "x" ? [tx, area.x, area.width] : [ty, area.y, area.height];
+			const moved = t + k * centre;
+			const shift = axis === "x" ? `translate(${moved - centre} 0)` : `translate(0 ${moved - centre})`;
+			e.setAttribute("transform", `${shift} ${transform}`.trim());
+			// プロット領域の外へ出た目盛は隠す
+			e.style.display = lo - 0.5 <= moved && moved <= lo + length + 0.5 ? "" : "none";
+		});
+	};
+	const toPaper = e => {
+		const p = svg.createSVGPoint();
+		p.x = e.clientX;
+		p.y = e.clientY;
+		return p.matrixTransform(svg.getScreenCTM().inverse());
+	};
+	const inside = p => area.x <= p.x && p.x <= area.x + area.width && area.y <= p.y && p.y <= area.y + area.height;
+	const hover = e => {
+		const p = toPaper(e);
+		const q = { x: (p.x - tx) / k, y: (p.y - ty) / k };
+		// 画面上で8px以内の最も近い点
+		let best = null, limit = 8 / svg.getScreenCTM().a / k;
+		for (const d of DATA) {
+			const dist = Math.hypot(d[0] - q.x, d[1] - q.y);
+			if (dist < limit) {
+				best = d;
+				limit = dist;
+			}
+		}
+		if (!inside(p) || best === null) {
+			tooltip.style.display = "none";
+			return;
+		}
+		const label = best[3] === "" ? "" : ` (${best[3]})`;
+		tooltip.textContent = `${best[2]}${label}\nx: ${best[4]}\ny: ${best[5]}`;
+		tooltip.style.left = `${e.pageX + 12}px`;
+		tooltip.style.top = `${e.pageY + 12}px`;
+		tooltip.style.display = "block";
+	};
+	svg.addEventListener("wheel", e => {
+		const p = toPaper(e);
+		if (!inside(p)) return;
+		e.preventDefault();
+		const next = Math.min(Math.max(k * Math.exp(-e.deltaY * 0.002), 1), 1000);
+		// カーソルの下の点を動かさずに拡大する
+		tx = p.x - (p.x - tx) * next / k;
+		ty = p.y - (p.y - ty) * next / k;
+		k = next;
+		apply();
+	}, { passive: false });
+	svg.addEventListener("pointerdown", e => {
+		const p = toPaper(e);
+		if (!inside(p)) return;
+		drag = { x: p.x - tx, y: p.y - ty };
+		svg.setPointerCapture(e.pointerId);
+	});
+	svg.addEventListener("pointermove", e => {
+		if (drag === null) {
+			hover(e);
+			return;
+		}
+		const p = toPaper(e);
+		tx = p.x - drag.x;
+		ty = p.y - drag.y;
+		apply();
+	});
+	svg.addEventListener("pointerup", () => drag = null);
+	svg.addEventListener("pointerleave", () => tooltip.style.display = "none");
+	svg.addEventListener("dblclick", () => {
+		k = 1;
+		tx = 0;
+		ty = 0;
+		apply();
+	});
+})();
+"##;
+
+/// JSONの文字列にする。`</script>`で閉じないよう`<`も逃がす
+struct JsonString<'a>(&'a str);
+impl fmt::Display for JsonString<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("\"")?;
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '<' => f.write_str("\\u003c")?,
+                c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+                _ => write!(f, "{}", c)?
+            }
+        }
+        f.write_str("\"")
+    }
+}
+
+impl Graph {
+    /// SVGと値の表示 / 拡大のためのスクリプトを埋め込んだHTMLを`out`へ書き出す。外部のファイルは読まない
+    pub fn write_html<W:Write>(&self, out:W) -> Result<W, Error> {
+        let mut out = BufWriter::new(out);
+        writeln!(out, "<!DOCTYPE html>")?;
+        writeln!(out, "<html>")?;
+        writeln!(out, "<head>")?;
+        writeln!(out, "<meta charset=\"utf-8\">")?;
+        writeln!(out, "<title>{}</title>", Escaped(&self.graph_paper.name))?;
+        write!(out, "<style>\n{}</style>\n", STYLE)?;
+        writeln!(out, "</head>")?;
+        writeln!(out, "<body>")?;
+        let mut backend = SvgBackend::new(out, self.graph_paper.size)?;
+        self.render(&mut backend)?;
+        let mut out = backend.finish()?;
+        writeln!(out)?;
+        writeln!(out, "<div id=\"tooltip\"></div>")?;
+        writeln!(out, "<script>")?;
+        let margin = self.graph_paper.margin;
+        let size = self.graph_paper.size - Vec2::vec2(margin, margin).mul(2_f32);
+        writeln!(out, "const AREA = [{}, {}, {}, {}];", margin, margin, size.x, size.y)?;
+        self.write_data(&mut out)?;
+        write!(out, "{}", SCRIPT)?;
+        writeln!(out, "</script>")?;
+        writeln!(out, "</body>")?;
+        writeln!(out, "</html>")?;
+        Ok(out.into_inner().map_err(|e| e.into_error())?)
+    }
+    /// 値を示す点を、軸で用紙の位置に写して`DATA`として書く。値は軸に合わせて書き表す
+    fn write_data<W:Write>(&self, out:&mut W) -> std::io::Result<()> {
+        let x = self.x_scale.to_scaled_x(&self.graph_paper);
+        let y = self.y_scale.to_scaled_y(&self.graph_paper);
+        let points = self.graph_paper.points.iter()
+            .map(|p| ("points".to_string(), String::new(), *p, *p));
+        let series = self.series.iter()
+            .enumerate()
+            .flat_map(|(i, s)| s.get_data_points().into_iter().map(move |d| {
+                let name = if d.name.is_empty() { format!("series {}", i + 1) } else { d.name };
+                (name, d.label, d.position, d.value)
+            }));
+        writeln!(out, "const DATA = [")?;
+        for (name, label, position, value) in points.chain(series) {
+            let mapped = Vec2::vec2(x(position.x), y(position.y));
+            if !(mapped.x.is_finite() && mapped.y.is_finite() && value.x.is_finite() && value.y.is_finite()) {
+                continue;
+            }
+            writeln!(
+                out,
+                "\t[{}, {}, {}, {}, {}, {}],",
+                mapped.x, mapped.y, JsonString(&name), JsonString(&label),
+                JsonString(&self.x_scale.format_x(value.x)),
+                JsonString(&self.y_scale.format_y(value.y))
+            )?;
+        }
+        writeln!(out, "];")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph_paper::{GraphPaper, XCategoryScale, YTimeScale};
+    use crate::series::{BarChart, BarLayout, BarSeries};
+    use super::*;
+
+    fn graph() -> Graph {
+        Graph {
+            graph_paper: GraphPaper {
+                name: String::new(),
+                margin: 100_f32,
+                size: Vec2::vec2(1000_f32, 800_f32),
+                points: vec![Vec2::vec2(1_f32, 3600_f32)],
+                stroke_width: 1_f32,
+                great_split_length: 20_f32,
+                short_split_length: 10_f32
+            },
+            x_scale: Box::new(XCategoryScale::from_labels(["A", "B"])),
+            y_scale: Box::new(YTimeScale {
+                // 2024-05-01T00:00:00Z
+                start: 1714521600_f64,
+                end: 1714608000_f64,
+                v_great_split: 4,
+                v_short_split: 2,
+                reversed: false
+            }),
+            series: vec![Box::new(BarChart {
+                series: vec![BarSeries { name: "<b>".to_string(), values: vec![60_f32], fill: "red".to_string() }],
+                layout: BarLayout::Grouped,
+                width: 0.8
+            })]
+        }
+    }
+
+    #[test]
+    fn data_uses_axis_values() {
+        let mut out = Vec::new();
+        graph().write_data(&mut out).unwrap();
+        let data = String::from_utf8(out).unwrap();
+        assert!(data.contains(r#""points", "", "B", "2024-05-01 01:00:00"]"#), "{}", data);
+        assert!(data.contains(r#""\u003cb>", "", "A", "2024-05-01 00:01:00"]"#), "{}", data);
+    }
+    #[test]
+    fn script_finds_plot_by_id() {
+        let html = String::from_utf8(graph().write_html(Vec::new()).unwrap()).unwrap();
+        assert!(html.contains("<g id=\"plot-data\">"), "{}", html);
+        assert!(html.contains("const AREA = [100, 100, 800, 600];"), "{}", html);
+    }
+}
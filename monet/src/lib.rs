@@ -1,5 +1,48 @@
 pub mod math;
 pub mod graph_paper;
+pub mod series;
+pub mod error;
+pub mod render;
+pub mod svg;
+pub mod tikz;
+pub mod eps;
+pub mod html;
+pub mod terminal;
+#[cfg(any(feature = "pdf", feature = "png"))]
+pub mod font;
+#[cfg(feature = "pdf")]
+pub mod pdf;
+#[cfg(feature = "png")]
+pub mod png;
+#[cfg(feature = "spec")]
+pub mod spec;
 
 pub use crate::graph_paper::{XLinearScale, XLogScale, YLinearScale, YLogScale};
+pub use crate::graph_paper::{XSymlogScale, YSymlogScale};
+pub use crate::graph_paper::{XReciprocalScale, YReciprocalScale};
+pub use crate::graph_paper::{XPowerScale, YPowerScale, XFunctionScale, YFunctionScale};
+pub use crate::graph_paper::{XBrokenScale, YBrokenScale};
+pub use crate::graph_paper::{XTimeScale, YTimeScale};
+pub use crate::graph_paper::XCategoryScale;
+pub use crate::graph_paper::{Graph, PointReport};
+pub use crate::graph_paper::{XSecondaryScale, YSecondaryScale};
+pub use crate::series::{Series, BarChart, BarSeries, BarLayout};
+pub use crate::series::{Histogram, Binning, Normalisation};
+pub use crate::series::{BoxPlot, BoxStatistics};
+pub use crate::series::{FillBetween, FillBase, Hatch};
+pub use crate::series::DataPoint;
+pub use crate::error::Error;
+pub use crate::render::Backend;
+pub use crate::svg::SvgBackend;
+pub use crate::tikz::TikzBackend;
+pub use crate::eps::EpsBackend;
+pub use crate::terminal::{TerminalBackend, TerminalOptions, Glyphs};
+#[cfg(any(feature = "pdf", feature = "png"))]
+pub use crate::font::Font;
+#[cfg(feature = "pdf")]
+pub use crate::pdf::PdfBackend;
+#[cfg(feature = "png")]
+pub use crate::png::{PngBackend, PngOptions, Resolution};
+#[cfg(feature = "spec")]
+pub use crate::spec::{GraphSpec, XScaleSpec, YScaleSpec, SeriesSpec, CsvSource, CsvColumns};
 pub use crate::{graph_paper::GraphPaper, math::vector2::Vec2};
\ No newline at end of file
@@ -1,3 +1,6 @@
 pub mod vector2;
+pub mod ticks;
+pub mod calendar;
+pub mod statistics;
 
-pub use vector2::Vec2;
\ No newline at end of file
+pub use vector2::Vec2;
@@ -0,0 +1,423 @@
+use crate::error::Error;
+use crate::math::ticks::MAX_TICKS;
+
+/// 1970-01-01からの日数 (先発グレゴリオ暦)
+fn days_from_civil(year:i64, month:u32, day:u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// 1970-01-01からの日数を (年, 月, 日) にする
+fn civil_from_days(days:i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// 日時 (UTC)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DateTime {
+    pub year  : i64,
+    pub month : u32,
+    pub day   : u32,
+    pub hour  : u32,
+    pub minute: u32,
+    pub second: f64,
+}
+impl DateTime {
+    pub fn from_epoch(epoch:f64) -> Self {
+        let days = (epoch / 86400_f64).floor();
+        let seconds = epoch - days * 86400_f64;
+        let (year, month, day) = civil_from_days(days as i64);
+        DateTime {
+            year, month, day,
+            hour  : (seconds / 3600_f64) as u32,
+            minute: (seconds % 3600_f64 / 60_f64) as u32,
+            second: seconds % 60_f64,
+        }
+    }
+    pub fn to_epoch(&self) -> f64 {
+        days_from_civil(self.year, self.month, self.day) as f64 * 86400_f64
+            + self.hour as f64 * 3600_f64
+            + self.minute as f64 * 60_f64
+            + self.second
+    }
+}
+
+/// 月の日数
+fn month_length(year:i64, month:u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31
+    }
+}
+
+/// "YYYY"、"YYYY-MM"、"YYYY-MM-DD" ('/'区切りも可) を (年, 月, 日) にする。省いた月日は1とする
+fn parse_date(text:&str) -> Option<(i64, u32, u32)> {
+    let mut parts = text.split(['-', '/']);
+    let year = parts.next()?;
+    // 年だけの場合はUNIX時刻と区別するため4桁に限る
+    if year.len() != 4 || !year.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let year = year.parse::<i64>().ok()?;
+    let month = parts.next().map_or(Some(1), |m| m.parse::<u32>().ok())?;
+    let day = parts.next().map_or(Some(1), |d| d.parse::<u32>().ok())?;
+    if parts.next().is_some() || !(1..=12).contains(&month)
+        || !(1..=month_length(year, month)).contains(&day) {
+        return None;
+    }
+    Some((year, month, day))
+}
+
+/// "HH:MM[:SS[.sss]]" を秒にする
+fn parse_clock(text:&str) -> Option<f64> {
+    let mut parts = text.split(':');
+    let hour = parts.next()?.parse::<u32>().ok()?;
+    let minute = parts.next()?.parse::<u32>().ok()?;
+    let second = match parts.next() {
+        Some(s) => s.parse::<f64>().ok()?,
+        None => 0_f64
+    };
+    if parts.next().is_some() || hour > 24 || minute > 59 || !(0_f64..61_f64).contains(&second) {
+        return None;
+    }
+    // 24時は一日の終わりの24:00:00だけを認める
+    if hour == 24 && (minute > 0 || second > 0_f64) {
+        return None;
+    }
+    Some(hour as f64 * 3600_f64 + minute as f64 * 60_f64 + second)
+}
+
+/// "Z", "+09:00", "-0500" などを秒にする
+fn parse_offset(text:&str) -> Option<f64> {
+    if text.is_empty() || text == "Z" {
+        return Some(0_f64);
+    }
+    let sign = match text.chars().next()? {
+        '+' => 1_f64,
+        '-' => -1_f64,
+        _ => return None
+    };
+    let digits = text[1..].replace(':', "");
+    if digits.len() != 4 {
+        return None;
+    }
+    let hour = digits[..2].parse::<u32>().ok()?;
+    let minute = digits[2..].parse::<u32>().ok()?;
+    Some(sign * (hour as f64 * 3600_f64 + minute as f64 * 60_f64))
+}
+
+/// 時刻の文字列をUNIX時刻 (秒) にする。
+/// ISO-8601 ("2024-05-01T12:34:56.789+09:00" など)、"2024/05/01 12:34"、日付のみ ("2024"、"2024-05" も可)、
+/// UNIX時刻の数値に対応する。4桁の数字は年として読む
+pub fn parse_timestamp(text:&str) -> Option<f64> {
+    let text = text.trim();
+    parse_calendar(text).or_else(|| text.parse::<f64>().ok().filter(|epoch| epoch.is_finite()))
+}
+
+/// 暦の形式の時刻をUNIX時刻 (秒) にする
+fn parse_calendar(text:&str) -> Option<f64> {
+    let (date, rest) = match text.find(['T', ' ']) {
+        Some(i) => (&text[..i], text[i + 1..].trim()),
+        None => (text, "")
+    };
+    let (year, month, day) = parse_date(date)?;
+    // 時差の記号を探す。日付側の'-'と混同しないよう時刻部分のみを見る
+    let (clock, offset) = match rest.find(['Z', '+', '-']) {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "")
+    };
+    let seconds = if clock.is_empty() { 0_f64 } else { parse_clock(clock)? };
+    let offset = parse_offset(offset)?;
+    Some(days_from_civil(year, month, day) as f64 * 86400_f64 + seconds - offset)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum TimeUnit {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Month,
+    Year
+}
+impl TimeUnit {
+    /// おおよその長さ (秒)
+    fn seconds(&self) -> f64 {
+        match self {
+            TimeUnit::Second => 1_f64,
+            TimeUnit::Minute => 60_f64,
+            TimeUnit::Hour   => 3600_f64,
+            TimeUnit::Day    => 86400_f64,
+            TimeUnit::Month  => 2629746_f64,
+            TimeUnit::Year   => 31556952_f64,
+        }
+    }
+}
+
+const TIME_STEPS:[(TimeUnit, u32); 31] = [
+    (TimeUnit::Second, 1), (TimeUnit::Second, 2), (TimeUnit::Second, 5),
+    (TimeUnit::Second, 10), (TimeUnit::Second, 15), (TimeUnit::Second, 30),
+    (TimeUnit::Minute, 1), (TimeUnit::Minute, 2), (TimeUnit::Minute, 5),
+    (TimeUnit::Minute, 10), (TimeUnit::Minute, 15), (TimeUnit::Minute, 30),
+    (TimeUnit::Hour, 1), (TimeUnit::Hour, 2), (TimeUnit::Hour, 3),
+    (TimeUnit::Hour, 6), (TimeUnit::Hour, 12),
+    (TimeUnit::Day, 1), (TimeUnit::Day, 2), (TimeUnit::Day, 7),
+    (TimeUnit::Month, 1), (TimeUnit::Month, 2), (TimeUnit::Month, 3), (TimeUnit::Month, 6),
+    (TimeUnit::Year, 1), (TimeUnit::Year, 2), (TimeUnit::Year, 5), (TimeUnit::Year, 10),
+    (TimeUnit::Year, 20), (TimeUnit::Year, 50), (TimeUnit::Year, 100),
+];
+
+fn format_time(epoch:f64, unit:TimeUnit) -> String {
+    // 秒を丸めてから分解し、":60"にならないよう分へ繰り上げる
+    let t = DateTime::from_epoch(epoch.round());
+    let midnight = t.hour == 0 && t.minute == 0 && t.second == 0_f64;
+    match unit {
+        TimeUnit::Second if midnight => format!("{:04}-{:02}-{:02}", t.year, t.month, t.day),
+        TimeUnit::Second => format!("{:02}:{:02}:{:02}", t.hour, t.minute, t.second as u32),
+        TimeUnit::Minute | TimeUnit::Hour if midnight => format!("{:04}-{:02}-{:02}", t.year, t.month, t.day),
+        TimeUnit::Minute | TimeUnit::Hour => format!("{:02}:{:02}", t.hour, t.minute),
+        TimeUnit::Day => format!("{:04}-{:02}-{:02}", t.year, t.month, t.day),
+        TimeUnit::Month => format!("{:04}-{:02}", t.year, t.month),
+        TimeUnit::Year => format!("{:04}", t.year),
+    }
+}
+
+/// UNIX時刻を秒単位で"YYYY-MM-DD HH:MM:SS"と書く
+pub fn format_timestamp(epoch:f64) -> String {
+    let t = DateTime::from_epoch(epoch.round());
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        t.year, t.month, t.day, t.hour, t.minute, t.second as u32
+    )
+}
+
+/// 長目盛の (UNIX時刻, ラベル) と、短目盛のUNIX時刻
+pub type CalendarTicks = (Vec<(f64, String)>, Vec<f64>);
+
+/// 約`count`個の長目盛となる暦の刻み
+fn calendar_step(lo:f64, hi:f64, count:u32) -> (TimeUnit, u32) {
+    let target = (hi - lo) / count.max(1) as f64;
+    TIME_STEPS.iter()
+        .copied()
+        .find(|(unit, n)| unit.seconds() * *n as f64 >= target)
+        .unwrap_or(TIME_STEPS[TIME_STEPS.len() - 1])
+}
+
+/// `calendar_ticks`で振る目盛の数が上限以下か確かめる
+pub fn validate_calendar_ticks(start:f64, end:f64, count:u32, short_split:u32) -> Result<(), Error> {
+    let (lo, hi) = (start.min(end), start.max(end));
+    if !(lo.is_finite() && hi.is_finite()) || lo == hi {
+        return Ok(());
+    }
+    let (unit, n) = calendar_step(lo, hi, count);
+    // 月の長さの違いと両端の分を見込んで多めに数える
+    let ticks = ((hi - lo) / (unit.seconds() * n as f64 * 0.9) + 2_f64) * short_split.max(1) as f64;
+    if ticks > MAX_TICKS as f64 {
+        return Err(Error::TooManyTicks(ticks as u64));
+    }
+    Ok(())
+}
+
+/// 年月を1970年1月からの月数で数えた`month`の月初めのUNIX時刻
+fn month_start(month:i64) -> f64 {
+    DateTime {
+        year: month.div_euclid(12),
+        month: month.rem_euclid(12) as u32 + 1,
+        day: 1, hour: 0, minute: 0, second: 0_f64
+    }.to_epoch()
+}
+
+/// `start`から`end`までに約`count`個の暦に沿った目盛を振る。
+/// 長目盛の (UNIX時刻, ラベル) と、短目盛のUNIX時刻を返す。目盛が多過ぎればエラーにする
+pub fn calendar_ticks(start:f64, end:f64, count:u32, short_split:u32) -> Result<CalendarTicks, Error> {
+    validate_calendar_ticks(start, end, count, short_split)?;
+    let (lo, hi) = (start.min(end), start.max(end));
+    if !(lo.is_finite() && hi.is_finite()) || lo == hi {
+        return Ok((Vec::new(), Vec::new()));
+    }
+    let (unit, n) = calendar_step(lo, hi, count);
+    let mut greats = Vec::new();
+    let mut shorts = Vec::new();
+    match unit {
+        TimeUnit::Month | TimeUnit::Year => {
+            let months = if unit == TimeUnit::Year { 12 * n as i64 } else { n as i64 };
+            let split = short_split.max(1) as i64;
+            let first = DateTime::from_epoch(lo);
+            let index = (first.year * 12 + first.month as i64 - 1).div_euclid(months) * months;
+            for m in (0..).map(|i:i64| index + i * months).take_while(|m| month_start(*m) <= hi) {
+                let (from, to) = (month_start(m), month_start(m + months));
+                if from >= lo {
+                    greats.push((from, format_time(from, unit)));
+                }
+                // 月の数で割り切れれば月初めに、割り切れなければ期間を等分して短目盛を振る
+                for k in 1..split {
+                    let epoch = if months % split == 0 {
+                        month_start(m + months / split * k)
+                    } else {
+                        from + (to - from) * k as f64 / split as f64
+                    };
+                    if lo <= epoch && epoch <= hi {
+                        shorts.push(epoch);
+                    }
+                }
+            }
+        },
+        _ => {
+            let step = unit.seconds() * n as f64;
+            let short_step = step / short_split.max(1) as f64;
+            let first = (lo / short_step).ceil() as i64;
+            let last = (hi / short_step).floor() as i64;
+            for i in first..last + 1 {
+                let epoch = i as f64 * short_step;
+                if (epoch / step - (epoch / step).round()).abs() < 1e-9 {
+                    greats.push((epoch, format_time(epoch, unit)));
+                } else {
+                    shorts.push(epoch);
+                }
+            }
+        }
+    }
+    Ok((greats, shorts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-05-01T00:00:00Z
+    const MAY_DAY:f64 = 1714521600_f64;
+
+    #[test]
+    fn parse_iso() {
+        assert_eq!(parse_timestamp("2024-05-01T00:00:00Z"), Some(MAY_DAY));
+        assert_eq!(parse_timestamp("2024-05-01T12:34:56.5Z"), Some(MAY_DAY + 45296.5));
+        assert_eq!(parse_timestamp("2024-05-01T09:00:00+09:00"), Some(MAY_DAY));
+        assert_eq!(parse_timestamp("2024-04-30T19:00:00-05:00"), Some(MAY_DAY));
+        assert_eq!(parse_timestamp("2024-04-30T19:00-0500"), Some(MAY_DAY));
+    }
+
+    #[test]
+    fn parse_other_forms() {
+        assert_eq!(parse_timestamp("2024/05/01 12:34"), Some(MAY_DAY + 45240_f64));
+        assert_eq!(parse_timestamp("2024-05-01"), Some(MAY_DAY));
+        assert_eq!(parse_timestamp(" 2024/5/1 "), Some(MAY_DAY));
+        assert_eq!(parse_timestamp("1714521600"), Some(MAY_DAY));
+        // 4桁の数字は年、年月だけなら月初め
+        assert_eq!(parse_timestamp("2024"), Some(1704067200_f64));
+        assert_eq!(parse_timestamp("2024-05"), Some(MAY_DAY));
+        assert_eq!(parse_timestamp("2024-04-30T24:00"), Some(MAY_DAY));
+        assert_eq!(parse_timestamp("2024.5"), Some(2024.5));
+        assert_eq!(parse_timestamp("-86400"), Some(-86400_f64));
+        assert_eq!(parse_timestamp("1969-12-31"), Some(-86400_f64));
+    }
+
+    #[test]
+    fn reject_impossible_dates() {
+        assert_eq!(parse_timestamp("2024-02-31"), None);
+        assert_eq!(parse_timestamp("2024-04-31"), None);
+        assert_eq!(parse_timestamp("2023-02-29"), None);
+        assert_eq!(parse_timestamp("1900-02-29"), None);
+        assert!(parse_timestamp("2024-02-29").is_some());
+        assert!(parse_timestamp("2000-02-29").is_some());
+        assert_eq!(parse_timestamp("2024-13-01"), None);
+        assert_eq!(parse_timestamp("2024-05-01T25:00"), None);
+        assert_eq!(parse_timestamp("2024-05-01T24:30"), None);
+        assert_eq!(parse_timestamp("2024-05-01T24:00:01"), None);
+        assert_eq!(parse_timestamp("24-05-01"), None);
+        assert_eq!(parse_timestamp("yesterday"), None);
+        assert_eq!(parse_timestamp("inf"), None);
+    }
+
+    #[test]
+    fn format_carries_seconds() {
+        assert_eq!(format_time(MAY_DAY + 59.9996, TimeUnit::Second), "00:01:00");
+        assert_eq!(format_time(MAY_DAY + 3599.7, TimeUnit::Second), "01:00:00");
+        assert_eq!(format_time(MAY_DAY - 0.2, TimeUnit::Second), "2024-05-01");
+        assert_eq!(format_time(MAY_DAY + 12.4, TimeUnit::Second), "00:00:12");
+    }
+
+    #[test]
+    fn format_full_timestamp() {
+        assert_eq!(format_timestamp(MAY_DAY + 45296.6), "2024-05-01 12:34:57");
+        assert_eq!(format_timestamp(MAY_DAY - 0.4), "2024-05-01 00:00:00");
+        assert_eq!(format_timestamp(-1_f64), "1969-12-31 23:59:59");
+    }
+
+    #[test]
+    fn ticks_within_a_day() {
+        // 10分を5分割すると2分刻み
+        let (greats, shorts) = calendar_ticks(MAY_DAY, MAY_DAY + 600_f64, 5, 2).unwrap();
+        let labels = greats.iter().map(|g| g.1.as_str()).collect::<Vec<_>>();
+        assert_eq!(labels, ["2024-05-01", "00:02", "00:04", "00:06", "00:08", "00:10"]);
+        assert_eq!(shorts.len(), 5);
+        // 日付を跨ぐと0時だけ日付を書く
+        let (greats, _) = calendar_ticks(MAY_DAY - 43200_f64, MAY_DAY + 43200_f64, 4, 1).unwrap();
+        let labels = greats.iter().map(|g| g.1.as_str()).collect::<Vec<_>>();
+        assert_eq!(labels, ["12:00", "18:00", "2024-05-01", "06:00", "12:00"]);
+    }
+
+    #[test]
+    fn ticks_across_months_and_years() {
+        let start = parse_timestamp("2023-11-15").unwrap();
+        let end = parse_timestamp("2024-03-15").unwrap();
+        let (greats, shorts) = calendar_ticks(start, end, 4, 1).unwrap();
+        let labels = greats.iter().map(|g| g.1.as_str()).collect::<Vec<_>>();
+        assert_eq!(labels, ["2023-12", "2024-01", "2024-02", "2024-03"]);
+        assert_eq!(greats[1].0, parse_timestamp("2024-01-01").unwrap());
+        assert!(shorts.is_empty());
+        let start = parse_timestamp("1995-06-01").unwrap();
+        let end = parse_timestamp("2024-06-01").unwrap();
+        let (greats, _) = calendar_ticks(start, end, 6, 1).unwrap();
+        let labels = greats.iter().map(|g| g.1.as_str()).collect::<Vec<_>>();
+        assert_eq!(labels, ["2000", "2005", "2010", "2015", "2020"]);
+    }
+
+    #[test]
+    fn month_and_year_ticks_have_short_ticks() {
+        let start = parse_timestamp("2023-11-15").unwrap();
+        let end = parse_timestamp("2024-03-15").unwrap();
+        // 1か月を2分割できないので期間を等分する
+        let (_, shorts) = calendar_ticks(start, end, 4, 2).unwrap();
+        assert_eq!(shorts.len(), 4);
+        assert_eq!(shorts[1], (parse_timestamp("2023-12-01").unwrap() + parse_timestamp("2024-01-01").unwrap()) / 2_f64);
+        // 1年を4分割すると四半期の月初め
+        let start = parse_timestamp("2019-06-01").unwrap();
+        let end = parse_timestamp("2024-06-01").unwrap();
+        let (greats, shorts) = calendar_ticks(start, end, 6, 4).unwrap();
+        assert_eq!(greats.first().map(|g| g.1.as_str()), Some("2020"));
+        assert_eq!(shorts[0], parse_timestamp("2019-07-01").unwrap());
+        assert_eq!(shorts[1], parse_timestamp("2019-10-01").unwrap());
+        assert!(shorts.contains(&parse_timestamp("2024-04-01").unwrap()));
+    }
+
+    #[test]
+    fn too_many_ticks_are_an_error() {
+        let start = parse_timestamp("1900-01-01").unwrap();
+        let end = parse_timestamp("2100-01-01").unwrap();
+        assert!(matches!(calendar_ticks(start, end, 4000000000, 1), Err(Error::TooManyTicks(_))));
+        assert!(matches!(calendar_ticks(start, end, 1000, 100), Err(Error::TooManyTicks(_))));
+        assert!(calendar_ticks(start, end, 100, 10).is_ok());
+    }
+
+    #[test]
+    fn empty_range_has_no_ticks() {
+        assert_eq!(calendar_ticks(MAY_DAY, MAY_DAY, 5, 2), Ok((Vec::new(), Vec::new())));
+        assert_eq!(calendar_ticks(f64::NAN, MAY_DAY, 5, 2), Ok((Vec::new(), Vec::new())));
+    }
+}
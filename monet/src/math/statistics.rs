@@ -0,0 +1,111 @@
+/// 有限の値だけを昇順に並べる
+pub fn sorted(values:&[f32]) -> Vec<f32> {
+    let mut sorted = values.iter()
+        .copied()
+        .filter(|v| v.is_finite())
+        .collect::<Vec<f32>>();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    sorted
+}
+
+pub fn mean(values:&[f32]) -> f32 {
+    values.iter().sum::<f32>() / values.len() as f32
+}
+
+/// 標本標準偏差
+pub fn standard_deviation(values:&[f32]) -> f32 {
+    if values.len() < 2 {
+        return 0_f32;
+    }
+    let mean = mean(values);
+    let sum = values.iter().map(|v| (v - mean).powi(2)).sum::<f32>();
+    (sum / (values.len() - 1) as f32).sqrt()
+}
+
+/// 昇順に並んだ値の`q`分位点 (線形補間)
+pub fn quantile(sorted:&[f32], q:f32) -> f32 {
+    if sorted.is_empty() {
+        return f32::NAN;
+    }
+    let position = q.clamp(0_f32, 1_f32) * (sorted.len() - 1) as f32;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f32)
+}
+
+/// 正規分布の確率密度
+pub fn normal_pdf(x:f32, mean:f32, standard_deviation:f32) -> f32 {
+    let z = (x - mean) / standard_deviation;
+    (-0.5 * z * z).exp() / (standard_deviation * (2_f32 * std::f32::consts::PI).sqrt())
+}
+
+/// Silvermanの目安によるカーネル密度推定のバンド幅
+pub fn silverman_bandwidth(sorted:&[f32]) -> f32 {
+    let iqr = quantile(sorted, 0.75) - quantile(sorted, 0.25);
+    let spread = match (standard_deviation(sorted), iqr / 1.34) {
+        (sd, iqr) if iqr > 0_f32 => sd.min(iqr),
+        (sd, _) => sd
+    };
+    0.9 * spread * (sorted.len() as f32).powf(-0.2)
+}
+
+/// ガウスカーネルによる密度推定
+pub fn kernel_density(values:&[f32], x:f32, bandwidth:f32) -> f32 {
+    values.iter()
+        .map(|v| normal_pdf(x, *v, bandwidth))
+        .sum::<f32>() / values.len() as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorted_drops_non_finite() {
+        assert_eq!(sorted(&[3.0, f32::NAN, -1.0, f32::INFINITY, 2.0]), [-1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn mean_and_deviation() {
+        let values = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        assert_eq!(mean(&values), 5_f32);
+        assert!((standard_deviation(&values) - (32_f32 / 7_f32).sqrt()).abs() < 1e-6);
+        assert_eq!(standard_deviation(&[1.0]), 0_f32);
+    }
+
+    #[test]
+    fn quantile_interpolates() {
+        let values = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+        assert_eq!(quantile(&values, 0.0), 1_f32);
+        assert_eq!(quantile(&values, 0.25), 3.25);
+        assert_eq!(quantile(&values, 0.5), 5.5);
+        assert_eq!(quantile(&values, 0.75), 7.75);
+        assert_eq!(quantile(&values, 1.0), 10_f32);
+        // 範囲外の割合は端に寄せる
+        assert_eq!(quantile(&values, 1.5), 10_f32);
+        assert_eq!(quantile(&[4.0], 0.25), 4_f32);
+        assert!(quantile(&[], 0.5).is_nan());
+    }
+
+    #[test]
+    fn bandwidth_uses_smaller_spread() {
+        let values = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+        // 標準偏差3.03より四分位範囲/1.34 = 3.36が大きい
+        let expected = 0.9 * standard_deviation(&values) * 10_f32.powf(-0.2);
+        assert!((silverman_bandwidth(&values) - expected).abs() < 1e-6);
+        let clustered = [0.0, 5.0, 5.0, 5.0, 5.0, 5.0, 5.0, 10.0];
+        // 四分位範囲が0なら標準偏差を使う
+        let expected = 0.9 * standard_deviation(&clustered) * 8_f32.powf(-0.2);
+        assert!((silverman_bandwidth(&clustered) - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn densities_integrate_to_one() {
+        let step = 0.01_f32;
+        let normal = (-1000..1000).map(|i| normal_pdf(i as f32 * step, 0.5, 1.5) * step).sum::<f32>();
+        assert!((normal - 1_f32).abs() < 1e-3);
+        let kernel = (-1000..1000).map(|i| kernel_density(&[-1.0, 0.0, 2.0], i as f32 * step, 0.8) * step)
+            .sum::<f32>();
+        assert!((kernel - 1_f32).abs() < 1e-3);
+    }
+}
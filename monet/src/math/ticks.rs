@@ -0,0 +1,179 @@
+/// 1本の軸に振れる目盛の数の上限
+pub const MAX_TICKS:u32 = 10000;
+
+/// `min`から`max`までを約`count`分割する、1・2・5系列のきりの良い目盛間隔
+pub fn nice_step(min:f32, max:f32, count:u32) -> f32 {
+    let raw = (max - min).abs() / count.max(1) as f32;
+    if !raw.is_finite() || raw <= 0_f32 {
+        return 1_f32;
+    }
+    let magnitude = 10_f32.powf(raw.log10().floor());
+    let normalised = raw / magnitude;
+    let nice = if normalised < 1.5 {
+        1_f32
+    } else if normalised < 3.0 {
+        2_f32
+    } else if normalised < 7.0 {
+        5_f32
+    } else {
+        10_f32
+    };
+    nice * magnitude
+}
+
+/// `min`から`max`の範囲に収まる`step`の倍数を列挙する
+pub fn multiples_in(min:f32, max:f32, step:f32) -> Vec<f32> {
+    let (lo, hi) = if min <= max { (min, max) } else { (max, min) };
+    if !(lo.is_finite() && hi.is_finite() && step.is_finite()) || step <= 0_f32 {
+        return Vec::new();
+    }
+    // 端の値が丸め誤差で落ちないように僅かに広げる
+    let epsilon = step * 1e-4;
+    let first = ((lo - epsilon) / step).ceil() as i64;
+    let last = ((hi + epsilon) / step).floor() as i64;
+    (first..last + 1)
+        .map(|i| i as f32 * step)
+        .collect::<Vec<f32>>()
+}
+
+/// 目盛間隔に合わせた桁数で目盛の値を文字列にする
+pub fn format_tick(value:f32, step:f32) -> String {
+    let decimals = (-step.log10().floor()).max(0_f32) as usize;
+    let text = format!("{:.*}", decimals, value);
+    // "-0"や"-0.0"を避ける
+    if text.trim_start_matches('-').chars().all(|c| c == '0' || c == '.') {
+        text.trim_start_matches('-').to_string()
+    } else {
+        text
+    }
+}
+
+/// 変換後の単位でほぼ等間隔になるよう長目盛を選び、元の単位のきりの良い値に丸める。
+/// (値, 長目盛なら値を丸めた間隔) を小さい順に返す
+pub fn transformed_ticks(
+    range       : (f32, f32),
+    forward     : &dyn Fn(f32) -> f32,
+    inverse     : &dyn Fn(f32) -> f32,
+    great_split : u32,
+    short_split : u32,
+) -> Vec<(f32, Option<f32>)> {
+    let (min, max) = (range.0.min(range.1), range.0.max(range.1));
+    let (a, b) = (forward(min), forward(max));
+    let (lo, hi) = (a.min(b), a.max(b));
+    let great_step = nice_step(lo, hi, great_split);
+    // (元の単位の値, 変換後の値)
+    let mut raw = multiples_in(lo, hi, great_step)
+        .into_iter()
+        .map(|t| (inverse(t), t))
+        .filter(|(v, _)| v.is_finite())
+        .collect::<Vec<(f32, f32)>>();
+    raw.sort_by(|a, b| a.0.total_cmp(&b.0));
+    // 変換後の位置のずれが間隔の5%に収まる、最も粗いきりの良い値に丸める
+    let tolerance = great_step * 0.05;
+    let mut greats:Vec<(f32, f32)> = Vec::new();
+    for (i, &(v, t)) in raw.iter().enumerate() {
+        let gap = [i.checked_sub(1).map(|j| raw[j].0), raw.get(i + 1).map(|n| n.0)]
+            .into_iter()
+            .flatten()
+            .map(|n| (n - v).abs())
+            .fold(max - min, f32::min);
+        let Some((rounded, step)) = finer_steps(nice_step(0_f32, gap, 1))
+            .map(|step| (round_inside(v, step, min, max), step))
+            .find(|(rounded, _)| (forward(*rounded) - t).abs() <= tolerance)
+        else {
+            continue;
+        };
+        if min <= rounded && rounded <= max
+            && greats.last().is_none_or(|(last, _)| rounded - last > step * 1e-3) {
+            greats.push((rounded, step));
+        }
+    }
+    // 長目盛の間をきりの良い値で刻む。両端の外側は隣の間隔を使う
+    let mut ticks = greats.iter().map(|&(v, step)| (v, Some(step))).collect::<Vec<_>>();
+    let bounds = std::iter::once(min)
+        .chain(greats.iter().map(|g| g.0))
+        .chain(std::iter::once(max))
+        .collect::<Vec<f32>>();
+    for (i, pair) in bounds.windows(2).enumerate() {
+        let (from, to) = (pair[0], pair[1]);
+        // 両端の区間は長目盛同士の間隔から刻みを決める
+        let reference = match (i, greats.len()) {
+            (_, 0 | 1) => (min, max),
+            (0, _) => (greats[0].0, greats[1].0),
+            (i, n) if i == n => (greats[n - 2].0, greats[n - 1].0),
+            _ => (from, to)
+        };
+        let step = nice_step(reference.0, reference.1, short_split);
+        let epsilon = step * 1e-3;
+        ticks.extend(multiples_in(from, to, step)
+            .into_iter()
+            .filter(|v| (v - from).abs() > epsilon && (v - to).abs() > epsilon)
+            .map(|v| (v, None)));
+    }
+    ticks.sort_by(|a, b| a.0.total_cmp(&b.0));
+    ticks
+}
+
+/// `start`から細かくなる1・2・5系列の間隔。10桁で打ち切る
+fn finer_steps(start:f32) -> impl Iterator<Item = f32> {
+    let magnitude = 10_f32.powf(start.log10().floor());
+    (0..10)
+        .flat_map(move |i| [10_f32, 5_f32, 2_f32].map(|m| m * magnitude / 10_f32.powi(i)))
+        .filter(move |step| *step <= start * (1_f32 + 1e-4))
+}
+
+/// `step`の倍数に丸める。範囲の外へ出たら内側へ寄せる
+fn round_inside(v:f32, step:f32, min:f32, max:f32) -> f32 {
+    let rounded = (v / step).round() * step;
+    if rounded < min {
+        (v / step).ceil() * step
+    } else if rounded > max {
+        (v / step).floor() * step
+    } else {
+        rounded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sqrt(v:f32) -> f32 { v.abs().powf(0.5).copysign(v) }
+    fn square(v:f32) -> f32 { v.abs().powf(2.0).copysign(v) }
+
+    #[test]
+    fn power_half_ticks() {
+        let ticks = transformed_ticks((0_f32, 100_f32), &sqrt, &square, 10, 5);
+        let greats = ticks.iter().filter(|t| t.1.is_some()).map(|t| t.0).collect::<Vec<f32>>();
+        assert_eq!(greats, vec![0.0, 1.0, 4.0, 9.0, 16.0, 25.0, 36.0, 49.0, 64.0, 81.0, 100.0]);
+        // 変換後の間隔がほぼ揃う
+        for pair in greats.windows(2) {
+            let gap = sqrt(pair[1]) - sqrt(pair[0]);
+            assert!((0.5..1.5).contains(&gap), "{:?}", pair);
+        }
+        // 短目盛は長目盛の間にあり、重ならない
+        let mut previous = -1_f32;
+        for (value, _) in &ticks {
+            assert!(*value > previous && *value <= 100_f32);
+            previous = *value;
+        }
+        assert!(ticks.iter().any(|t| t.1.is_none() && 36.0 < t.0 && t.0 < 50.0));
+    }
+
+    #[test]
+    fn rounds_to_coarsest_close_value() {
+        // 波長 (nm) と光子のエネルギー (eV)
+        let ev = |v:f32| 1240_f32 / v;
+        let ticks = transformed_ticks((300_f32, 800_f32), &ev, &ev, 5, 2);
+        let greats = ticks.iter().filter(|t| t.1.is_some()).map(|t| t.0).collect::<Vec<f32>>();
+        assert_eq!(greats, vec![310.0, 355.0, 410.0, 500.0, 620.0]);
+    }
+
+    #[test]
+    fn identity_matches_linear_ticks() {
+        let ticks = transformed_ticks((0_f32, 10_f32), &|v| v, &|v| v, 5, 2);
+        let greats = ticks.iter().filter(|t| t.1.is_some()).map(|t| t.0).collect::<Vec<f32>>();
+        assert_eq!(greats, vec![0.0, 2.0, 4.0, 6.0, 8.0, 10.0]);
+        assert_eq!(ticks.len(), 11);
+    }
+}
@@ -1,6 +1,7 @@
 use std::fmt::Debug;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize), serde(from = "[f32; 2]", into = "[f32; 2]"))]
 pub struct Vec2 {
     pub x: f32,
     pub y: f32
@@ -22,6 +23,16 @@ impl Vec2 {
         Vec2::vec2(self.x.log(base), self.y.log(base))
     }
 }
+impl From<[f32; 2]> for Vec2 {
+    fn from([x, y]:[f32; 2]) -> Self {
+        Vec2 { x, y }
+    }
+}
+impl From<Vec2> for [f32; 2] {
+    fn from(v:Vec2) -> Self {
+        [v.x, v.y]
+    }
+}
 impl Debug for Vec2 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "({}, {})", self.x, self.y)
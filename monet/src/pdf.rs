@@ -0,0 +1,396 @@
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use miniz_oxide::deflate::compress_to_vec_zlib;
+use pdf_writer::types::{CidFontType, FontFlags, SystemInfo, UnicodeCmap};
+use pdf_writer::{Content, Filter, Finish, Name, Pdf, Rect, Ref, Str};
+use ttf_parser::Tag;
+
+use crate::error::Error;
+use crate::font::{self, Font};
+use crate::graph_paper::Graph;
+use crate::math::Vec2;
+use crate::render::{Backend, PathCommand, Style, Text};
+
+/// 用紙の1単位 (0.1mm) をPDFのpt (1/72インチ) にする係数
+const UNIT_TO_PT:f32 = 72_f32 / 254_f32;
+// 円をベジェ曲線4本で近似するときの制御点の距離
+const KAPPA:f32 = 0.552_284_8;
+// 圧縮の強さ (0 - 10)
+const COMPRESSION:u8 = 6;
+// フォントを渡されなかったときに使う標準フォントの寸法 (文字の大きさに対する割合)
+const HELVETICA_ASCENDER:f32 = 0.718;
+const HELVETICA_DESCENDER:f32 = -0.207;
+const HELVETICA_AVERAGE_WIDTH:f32 = 0.55;
+
+const FONT_NAME:Name<'static> = Name(b"F0");
+// WinAnsiEncodingの0x80から0x9Fの文字。Latin-1とはこの範囲が異なる (空きは`None`)
+const WIN_ANSI_HIGH:[Option<char>; 32] = [
+    Some('€'), None, Some('‚'), Some('ƒ'), Some('„'), Some('…'), Some('†'), Some('‡'),
+    Some('ˆ'), Some('‰'), Some('Š'), Some('‹'), Some('Œ'), None, Some('Ž'), None,
+    None, Some('‘'), Some('’'), Some('“'), Some('”'), Some('•'), Some('–'), Some('—'),
+    Some('˜'), Some('™'), Some('š'), Some('›'), Some('œ'), None, Some('ž'), Some('Ÿ'),
+];
+
+/// WinAnsiEncodingで文字を1バイトにする。表せない文字は`None`
+fn win_ansi(c:char) -> Option<u8> {
+    match c as u32 {
+        code @ (0x20..=0x7E | 0xA0..=0xFF) => Some(code as u8),
+        _ => WIN_ANSI_HIGH.iter().position(|h| *h == Some(c)).map(|i| 0x80 + i as u8)
+    }
+}
+
+/// 1ページのPDFを作る描画先。`finish`で文書を返す
+pub struct PdfBackend {
+    size: Vec2,
+    content: Content,
+    font: Option<Font>,
+    // 使った字形と、対応する文字
+    glyphs: BTreeMap<u16, char>,
+    // 使った塗りの不透明度。`GS{i}`として参照する
+    opacities: Vec<f32>,
+    // 標準フォントで書けなかった最初の文字
+    unencodable: Option<char>,
+}
+
+impl PdfBackend {
+    /// `font`を渡さなければ、WinAnsiEncodingの文字だけを標準のHelveticaで書く
+    pub fn new(size:Vec2, font:Option<Font>) -> Self {
+        let mut content = Content::new();
+        // 左上を原点とし、下向きをyの正とする用紙の座標に合わせる
+        content.transform([UNIT_TO_PT, 0_f32, 0_f32, -UNIT_TO_PT, 0_f32, size.y * UNIT_TO_PT]);
+        PdfBackend {
+            size,
+            content,
+            font,
+            glyphs: BTreeMap::new(),
+            opacities: Vec::new(),
+            unencodable: None
+        }
+    }
+
+    fn trace(&mut self, commands:&[PathCommand]) {
+        for command in commands {
+            match command {
+                PathCommand::MoveTo(p) => self.content.move_to(p.x, p.y),
+                PathCommand::LineTo(p) => self.content.line_to(p.x, p.y),
+                PathCommand::Close => self.content.close_path()
+            };
+        }
+    }
+
+    /// 色と太さを設定して、なぞった輪郭を塗る / 線を引く
+    fn paint(&mut self, style:&Style, fillable:bool) {
+        let fill = if fillable { style.fill_colour() } else { None };
+        let stroke = style.stroke_colour();
+        if let Some(opacity) = style.fill_opacity.filter(|_| fill.is_some()) {
+            let index = match self.opacities.iter().position(|o| *o == opacity) {
+                Some(i) => i,
+                None => {
+                    self.opacities.push(opacity);
+                    self.opacities.len() - 1
+                }
+            };
+            self.content.save_state();
+            self.content.set_parameters(Name(format!("GS{}", index).as_bytes()));
+        }
+        if let Some(colour) = fill {
+            let [r, g, b] = colour.to_unit();
+            self.content.set_fill_rgb(r, g, b);
+        }
+        if let Some(colour) = stroke {
+            let [r, g, b] = colour.to_unit();
+            self.content.set_stroke_rgb(r, g, b);
+            self.content.set_line_width(style.width());
+        }
+        match (fill, stroke) {
+            (Some(_), Some(_)) => self.content.fill_nonzero_and_stroke(),
+            (Some(_), None) => self.content.fill_nonzero(),
+            (None, Some(_)) => self.content.stroke(),
+            (None, None) => self.content.end_path()
+        };
+        if style.fill_opacity.is_some() && fill.is_some() {
+            self.content.restore_state();
+        }
+    }
+
+    /// 文書を書き出す
+    pub fn finish(self) -> Vec<u8> {
+        let mut pdf = Pdf::new();
+        let catalog_id = Ref::new(1);
+        let pages_id = Ref::new(2);
+        let page_id = Ref::new(3);
+        let content_id = Ref::new(4);
+        let font_id = Ref::new(5);
+        let mut next_id = Ref::new(6);
+        let mut alloc = || next_id.bump();
+
+        pdf.catalog(catalog_id).pages(pages_id);
+        pdf.pages(pages_id).kids([page_id]).count(1);
+
+        let opacity_ids = self.opacities.iter().map(|_| alloc()).collect::<Vec<Ref>>();
+        let mut page = pdf.page(page_id);
+        page.media_box(Rect::new(0_f32, 0_f32, self.size.x * UNIT_TO_PT, self.size.y * UNIT_TO_PT));
+        page.parent(pages_id);
+        page.contents(content_id);
+        let mut resources = page.resources();
+        resources.fonts().pair(FONT_NAME, font_id);
+        let mut states = resources.ext_g_states();
+        for (i, id) in opacity_ids.iter().enumerate() {
+            states.pair(Name(format!("GS{}", i).as_bytes()), *id);
+        }
+        states.finish();
+        resources.finish();
+        page.finish();
+
+        for (opacity, id) in self.opacities.iter().zip(&opacity_ids) {
+            pdf.ext_graphics(*id).non_stroking_alpha(*opacity);
+        }
+
+        let content = compress_to_vec_zlib(&self.content.finish(), COMPRESSION);
+        pdf.stream(content_id, &content).filter(Filter::FlateDecode);
+
+        match &self.font {
+            Some(font) => write_font(&mut pdf, font_id, &mut alloc, font, &self.glyphs),
+            None => {
+                pdf.type1_font(font_id)
+                    .base_font(Name(b"Helvetica"))
+                    .encoding_predefined(Name(b"WinAnsiEncoding"));
+            }
+        }
+        pdf.finish()
+    }
+}
+
+/// 使った字形だけを残したフォントを、Identity-Hで字形番号を直接指すType0フォントとして埋め込む
+fn write_font<F>(pdf:&mut Pdf, font_id:Ref, alloc:&mut F, font:&Font, glyphs:&BTreeMap<u16, char>)
+    where F: FnMut() -> Ref
+{
+    let cid_id = alloc();
+    let descriptor_id = alloc();
+    let cmap_id = alloc();
+    let file_id = alloc();
+
+    let face = font.face();
+    let units = face.units_per_em() as f32;
+    let to_pdf = |v:f32| v / units * 1000_f32;
+    let is_cff = face.raw_face().table(Tag::from_bytes(b"CFF ")).is_some();
+    // 部分集合の名前には6文字の大文字と`+`を付ける
+    let postscript = face.names()
+        .into_iter()
+        .filter(|n| n.name_id == ttf_parser::name_id::POST_SCRIPT_NAME)
+        .find_map(|n| n.to_string())
+        .unwrap_or_else(|| "Font".to_string());
+    let base_font = format!("MONETF+{}", postscript);
+    let system_info = SystemInfo {
+        registry: Str(b"Adobe"),
+        ordering: Str(b"Identity"),
+        supplement: 0
+    };
+
+    pdf.type0_font(font_id)
+        .base_font(Name(base_font.as_bytes()))
+        .encoding_predefined(Name(b"Identity-H"))
+        .descendant_font(cid_id)
+        .to_unicode(cmap_id);
+
+    let mut cid = pdf.cid_font(cid_id);
+    cid.subtype(if is_cff { CidFontType::Type0 } else { CidFontType::Type2 });
+    cid.base_font(Name(base_font.as_bytes()));
+    cid.system_info(system_info);
+    cid.font_descriptor(descriptor_id);
+    cid.default_width(0_f32);
+    if !is_cff {
+        cid.cid_to_gid_map_predefined(Name(b"Identity"));
+    }
+    let mut widths = cid.widths();
+    for glyph in glyphs.keys() {
+        let advance = face.glyph_hor_advance(ttf_parser::GlyphId(*glyph)).unwrap_or(0) as f32;
+        widths.consecutive(*glyph, [to_pdf(advance)]);
+    }
+    widths.finish();
+    cid.finish();
+
+    let bbox = face.global_bounding_box();
+    let mut descriptor = pdf.font_descriptor(descriptor_id);
+    descriptor
+        .name(Name(base_font.as_bytes()))
+        .flags(FontFlags::SYMBOLIC)
+        .bbox(Rect::new(
+            to_pdf(bbox.x_min as f32), to_pdf(bbox.y_min as f32),
+            to_pdf(bbox.x_max as f32), to_pdf(bbox.y_max as f32)
+        ))
+        .italic_angle(face.italic_angle())
+        .ascent(to_pdf(face.ascender() as f32))
+        .descent(to_pdf(face.descender() as f32))
+        .cap_height(to_pdf(face.capital_height().unwrap_or(face.ascender()) as f32))
+        .stem_v(80_f32);
+    if is_cff {
+        descriptor.font_file3(file_id);
+    } else {
+        descriptor.font_file2(file_id);
+    }
+    descriptor.finish();
+
+    let mut cmap = UnicodeCmap::new(Name(b"Custom"), system_info);
+    for (glyph, c) in glyphs {
+        cmap.pair(*glyph, *c);
+    }
+    pdf.cmap(cmap_id, &cmap.finish());
+
+    // 部分集合を作れなければフォント全体を埋め込む
+    let ids = glyphs.keys().copied().chain([0]).collect::<Vec<u16>>();
+    let data = subsetter::subset(font.data(), font.index(), subsetter::Profile::pdf(&ids))
+        .unwrap_or_else(|_| font.data().to_vec());
+    let compressed = compress_to_vec_zlib(&data, COMPRESSION);
+    let mut stream = pdf.stream(file_id, &compressed);
+    stream.filter(Filter::FlateDecode);
+    if is_cff {
+        stream.pair(Name(b"Subtype"), Name(b"OpenType"));
+    }
+}
+
+impl Backend for PdfBackend {
+    fn begin_group(&mut self, _id:Option<&str>, _class:Option<&str>) -> io::Result<()> {
+        self.content.save_state();
+        Ok(())
+    }
+    fn end_group(&mut self) -> io::Result<()> {
+        self.content.restore_state();
+        Ok(())
+    }
+    fn clip(&mut self, commands:&[PathCommand]) -> io::Result<()> {
+        self.trace(commands);
+        self.content.clip_nonzero();
+        self.content.end_path();
+        Ok(())
+    }
+    fn line(&mut self, from:Vec2, to:Vec2, style:&Style) -> io::Result<()> {
+        self.content.move_to(from.x, from.y);
+        self.content.line_to(to.x, to.y);
+        self.paint(style, false);
+        Ok(())
+    }
+    fn polyline(&mut self, points:&[Vec2], closed:bool, style:&Style) -> io::Result<()> {
+        self.trace(&PathCommand::through(points, closed));
+        self.paint(style, true);
+        Ok(())
+    }
+    fn path(&mut self, commands:&[PathCommand], style:&Style) -> io::Result<()> {
+        self.trace(commands);
+        self.paint(style, true);
+        Ok(())
+    }
+    fn rect(&mut self, origin:Vec2, size:Vec2, style:&Style) -> io::Result<()> {
+        self.content.rect(origin.x, origin.y, size.x, size.y);
+        self.paint(style, true);
+        Ok(())
+    }
+    fn circle(&mut self, centre:Vec2, radius:f32, style:&Style) -> io::Result<()> {
+        let (x, y, r, k) = (centre.x, centre.y, radius, radius * KAPPA);
+        self.content.move_to(x + r, y);
+        self.content.cubic_to(x + r, y + k, x + k, y + r, x, y + r);
+        self.content.cubic_to(x - k, y + r, x - r, y + k, x - r, y);
+        self.content.cubic_to(x - r, y - k, x - k, y - r, x, y - r);
+        self.content.cubic_to(x + k, y - r, x + r, y - k, x + r, y);
+        self.content.close_path();
+        self.paint(style, true);
+        Ok(())
+    }
+    fn text(&mut self, text:&Text) -> io::Result<()> {
+        let size = text.setting.size();
+        // 文字の向きを用紙の座標の反転に合わせて戻す
+        let matrix = |origin:Vec2| [size, 0_f32, 0_f32, -size, origin.x, origin.y];
+        self.content.begin_text();
+        self.content.set_font(FONT_NAME, 1_f32);
+        self.content.set_fill_rgb(0_f32, 0_f32, 0_f32);
+        match self.font.clone() {
+            Some(font) => {
+                let face = font.face();
+                for span in font.place(text) {
+                    let mut encoded = Vec::new();
+                    for c in span.text.chars() {
+                        let glyph = Font::glyph(&face, c).0;
+                        self.glyphs.entry(glyph).or_insert(c);
+                        encoded.extend(glyph.to_be_bytes());
+                    }
+                    self.content.set_text_matrix(matrix(span.origin));
+                    self.content.show(Str(&encoded));
+                }
+            },
+            None => {
+                let width = |s:&str, size:f32| s.chars().count() as f32 * HELVETICA_AVERAGE_WIDTH * size;
+                for span in font::place(text, width, HELVETICA_ASCENDER, HELVETICA_DESCENDER) {
+                    let encoded = span.text.chars()
+                        .map(|c| win_ansi(c).ok_or(c))
+                        .collect::<Result<Vec<u8>, char>>();
+                    let encoded = match encoded {
+                        Ok(encoded) => encoded,
+                        Err(c) => {
+                            self.unencodable = Some(c);
+                            return Err(io::Error::new(io::ErrorKind::InvalidData, Error::UnencodableText(c)));
+                        }
+                    };
+                    self.content.set_text_matrix(matrix(span.origin));
+                    self.content.show(Str(&encoded));
+                }
+            }
+        }
+        self.content.end_text();
+        Ok(())
+    }
+}
+
+impl Graph {
+    /// PDFを`out`へ書き出す。用紙の1単位を0.1mmとしてページの大きさを決める
+    pub fn write_pdf<W:Write>(&self, mut out:W, font:Option<Font>) -> Result<W, Error> {
+        let mut backend = PdfBackend::new(self.graph_paper.size, font);
+        let rendered = self.render(&mut backend);
+        if let Some(c) = backend.unencodable {
+            return Err(Error::UnencodableText(c));
+        }
+        rendered?;
+        out.write_all(&backend.finish())?;
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph_paper::{GraphPaper, XLinearScale, YLinearScale};
+    use super::*;
+
+    fn graph(name:&str) -> Graph {
+        Graph {
+            graph_paper: GraphPaper {
+                name: name.to_string(),
+                margin: 100_f32,
+                size: Vec2::vec2(1100_f32, 1100_f32),
+                points: Vec::new(),
+                stroke_width: 1_f32,
+                great_split_length: 20_f32,
+                short_split_length: 10_f32
+            },
+            x_scale: Box::new(XLinearScale { h_great_split: 5, h_short_split: 2, max_value: 10_f32, reversed: false }),
+            y_scale: Box::new(YLinearScale { v_great_split: 5, v_short_split: 2, max_value: 10_f32, reversed: false }),
+            series: Vec::new()
+        }
+    }
+
+    #[test]
+    fn win_ansi_differs_from_latin1() {
+        assert_eq!(win_ansi('A'), Some(b'A'));
+        assert_eq!(win_ansi('é'), Some(0xE9));
+        assert_eq!(win_ansi('€'), Some(0x80));
+        assert_eq!(win_ansi('—'), Some(0x97));
+        // Latin-1の制御文字はWinAnsiEncodingにない
+        assert_eq!(win_ansi('\u{80}'), None);
+        assert_eq!(win_ansi('日'), None);
+    }
+
+    #[test]
+    fn built_in_font_rejects_unencodable_text() {
+        assert!(graph("Café – €").write_pdf(Vec::new(), None).is_ok());
+        assert_eq!(graph("日本").write_pdf(Vec::new(), None).err(), Some(Error::UnencodableText('日')));
+    }
+}
@@ -0,0 +1,259 @@
+use std::io::{self, Write};
+
+use tiny_skia::{
+    Color, FillRule, LineCap, Mask, Paint, Path, PathBuilder, Pixmap, Rect, Stroke, Transform
+};
+use ttf_parser::OutlineBuilder;
+
+use crate::error::Error;
+use crate::font::Font;
+use crate::graph_paper::Graph;
+use crate::math::Vec2;
+use crate::render::{Backend, PathCommand, Rgb, Style, Text};
+
+/// 用紙の1単位 (0.1mm) の1インチに対する割合
+const UNITS_PER_INCH:f32 = 254_f32;
+
+/// 画像の大きさの決め方
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Resolution {
+    // 1インチあたりの画素数
+    Dpi(f32),
+    // 横の画素数。縦は用紙の縦横比に合わせる
+    Width(u32),
+}
+
+/// PNGの書き出しの設定
+#[derive(Clone)]
+pub struct PngOptions {
+    pub resolution: Resolution,
+    // 背景を白で塗らずに透明にする
+    pub transparent: bool,
+    // 文字を描くフォント。`None`なら同梱のDejaVu Sansを使う
+    pub font: Option<Font>,
+}
+impl PngOptions {
+    /// 用紙の1単位あたりの画素数
+    fn scale(&self, size:Vec2) -> f32 {
+        match self.resolution {
+            Resolution::Dpi(dpi) => dpi / UNITS_PER_INCH,
+            Resolution::Width(width) => width as f32 / size.x
+        }
+    }
+}
+
+/// 画素に塗る描画先。`finish`で画像を返す
+pub struct PngBackend {
+    pixmap: Pixmap,
+    transform: Transform,
+    font: Font,
+    // 開いているまとまりごとの切り抜き。`None`は外側と同じ
+    masks: Vec<Option<Mask>>,
+}
+
+impl PngBackend {
+    pub fn new(size:Vec2, options:&PngOptions) -> Result<Self, Error> {
+        let scale = options.scale(size);
+        let (width, height) = ((size.x * scale).round(), (size.y * scale).round());
+        let mut pixmap = Pixmap::new(width as u32, height as u32)
+            .ok_or(Error::InvalidParameter { name: "PNG width", value: width })?;
+        if !options.transparent {
+            pixmap.fill(Color::WHITE);
+        }
+        Ok(PngBackend {
+            pixmap,
+            transform: Transform::from_scale(scale, scale),
+            font: options.font.clone().unwrap_or_else(Font::bundled),
+            masks: Vec::new()
+        })
+    }
+
+    /// PNGとして書き出す
+    pub fn finish(self) -> io::Result<Vec<u8>> {
+        self.pixmap.encode_png().map_err(io::Error::other)
+    }
+
+    fn mask(&self) -> Option<&Mask> {
+        self.masks.iter().rev().find_map(|m| m.as_ref())
+    }
+
+    fn fill(&mut self, path:&Path, colour:Rgb, opacity:f32) {
+        let mut paint = Paint::default();
+        paint.set_color_rgba8(colour.r, colour.g, colour.b, (opacity.clamp(0_f32, 1_f32) * 255_f32).round() as u8);
+        paint.anti_alias = true;
+        let mask = self.masks.iter().rev().find_map(|m| m.as_ref());
+        self.pixmap.fill_path(path, &paint, FillRule::Winding, self.transform, mask);
+    }
+
+    /// 色と太さに従って塗る / 線を引く
+    fn paint(&mut self, path:Option<Path>, style:&Style, fillable:bool) {
+        let Some(path) = path else {
+            return;
+        };
+        if let Some(colour) = style.fill_colour().filter(|_| fillable) {
+            self.fill(&path, colour, style.fill_opacity.unwrap_or(1_f32));
+        }
+        if let Some(colour) = style.stroke_colour() {
+            let mut paint = Paint::default();
+            paint.set_color_rgba8(colour.r, colour.g, colour.b, 255);
+            paint.anti_alias = true;
+            let stroke = Stroke { width: style.width(), line_cap: LineCap::Butt, ..Default::default() };
+            let mask = self.masks.iter().rev().find_map(|m| m.as_ref());
+            self.pixmap.stroke_path(&path, &paint, &stroke, self.transform, mask);
+        }
+    }
+}
+
+fn trace(commands:&[PathCommand]) -> Option<Path> {
+    let mut builder = PathBuilder::new();
+    for command in commands {
+        match command {
+            PathCommand::MoveTo(p) => builder.move_to(p.x, p.y),
+            PathCommand::LineTo(p) => builder.line_to(p.x, p.y),
+            PathCommand::Close => builder.close()
+        }
+    }
+    builder.finish()
+}
+
+/// 字形の輪郭を用紙の座標に置き直しながらなぞる
+struct GlyphOutline {
+    builder: PathBuilder,
+    origin: Vec2,
+    // フォントの1単位の用紙での長さ
+    scale: f32,
+}
+impl GlyphOutline {
+    fn point(&self, x:f32, y:f32) -> (f32, f32) {
+        // フォントは上向きが正
+        (self.origin.x + x * self.scale, self.origin.y - y * self.scale)
+    }
+}
+impl OutlineBuilder for GlyphOutline {
+    fn move_to(&mut self, x:f32, y:f32) {
+        let (x, y) = self.point(x, y);
+        self.builder.move_to(x, y);
+    }
+    fn line_to(&mut self, x:f32, y:f32) {
+        let (x, y) = self.point(x, y);
+        self.builder.line_to(x, y);
+    }
+    fn quad_to(&mut self, x1:f32, y1:f32, x:f32, y:f32) {
+        let (x1, y1) = self.point(x1, y1);
+        let (x, y) = self.point(x, y);
+        self.builder.quad_to(x1, y1, x, y);
+    }
+    fn curve_to(&mut self, x1:f32, y1:f32, x2:f32, y2:f32, x:f32, y:f32) {
+        let (x1, y1) = self.point(x1, y1);
+        let (x2, y2) = self.point(x2, y2);
+        let (x, y) = self.point(x, y);
+        self.builder.cubic_to(x1, y1, x2, y2, x, y);
+    }
+    fn close(&mut self) {
+        self.builder.close();
+    }
+}
+
+impl Backend for PngBackend {
+    fn begin_group(&mut self, _id:Option<&str>, _class:Option<&str>) -> io::Result<()> {
+        self.masks.push(None);
+        Ok(())
+    }
+    fn end_group(&mut self) -> io::Result<()> {
+        self.masks.pop();
+        Ok(())
+    }
+    fn clip(&mut self, commands:&[PathCommand]) -> io::Result<()> {
+        let Some(path) = trace(commands) else {
+            return Ok(());
+        };
+        // 外側の切り抜きと重なる部分だけを残す
+        let mask = match self.mask() {
+            Some(outer) => {
+                let mut mask = outer.clone();
+                mask.intersect_path(&path, FillRule::Winding, true, self.transform);
+                mask
+            },
+            None => {
+                let mut mask = Mask::new(self.pixmap.width(), self.pixmap.height())
+                    .expect("the mask has the size of the pixmap");
+                mask.fill_path(&path, FillRule::Winding, true, self.transform);
+                mask
+            }
+        };
+        if let Some(top) = self.masks.last_mut() {
+            *top = Some(mask);
+        }
+        Ok(())
+    }
+    fn line(&mut self, from:Vec2, to:Vec2, style:&Style) -> io::Result<()> {
+        let mut builder = PathBuilder::new();
+        builder.move_to(from.x, from.y);
+        builder.line_to(to.x, to.y);
+        self.paint(builder.finish(), style, false);
+        Ok(())
+    }
+    fn polyline(&mut self, points:&[Vec2], closed:bool, style:&Style) -> io::Result<()> {
+        self.paint(trace(&PathCommand::through(points, closed)), style, true);
+        Ok(())
+    }
+    fn path(&mut self, commands:&[PathCommand], style:&Style) -> io::Result<()> {
+        self.paint(trace(commands), style, true);
+        Ok(())
+    }
+    fn rect(&mut self, origin:Vec2, size:Vec2, style:&Style) -> io::Result<()> {
+        let path = Rect::from_xywh(origin.x, origin.y, size.x, size.y).map(PathBuilder::from_rect);
+        self.paint(path, style, true);
+        Ok(())
+    }
+    fn circle(&mut self, centre:Vec2, radius:f32, style:&Style) -> io::Result<()> {
+        self.paint(PathBuilder::from_circle(centre.x, centre.y, radius), style, true);
+        Ok(())
+    }
+    fn text(&mut self, text:&Text) -> io::Result<()> {
+        if text.content().trim().is_empty() {
+            return Ok(());
+        }
+        let font = self.font.clone();
+        let face = font.face();
+        let scale = text.setting.size() / face.units_per_em() as f32;
+        let mut outline = GlyphOutline { builder: PathBuilder::new(), origin: text.anchor, scale };
+        for span in font.place(text) {
+            outline.origin = span.origin;
+            for c in span.text.chars() {
+                let glyph = Font::glyph(&face, c);
+                face.outline_glyph(glyph, &mut outline);
+                outline.origin.x += face.glyph_hor_advance(glyph).unwrap_or(0) as f32 * scale;
+            }
+        }
+        if let Some(path) = outline.builder.finish() {
+            self.fill(&path, Rgb::BLACK, 1_f32);
+        }
+        Ok(())
+    }
+}
+
+impl Graph {
+    /// PNGを`out`へ書き出す
+    pub fn write_png<W:Write>(&self, mut out:W, options:&PngOptions) -> Result<W, Error> {
+        let mut backend = PngBackend::new(self.graph_paper.size, options)?;
+        self.render(&mut backend)?;
+        out.write_all(&backend.finish()?)?;
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::TextSetting;
+
+    #[test]
+    fn text_without_font_uses_bundled_face() {
+        let options = PngOptions { resolution: Resolution::Dpi(96_f32), transparent: true, font: None };
+        let mut backend = PngBackend::new(Vec2::vec2(200_f32, 100_f32), &options).unwrap();
+        let setting = TextSetting { font_size: 12, v_anchor: None, h_anchor: None };
+        backend.text(&Text::new(Vec2::vec2(10_f32, 60_f32), "0.5".to_string(), setting)).unwrap();
+        assert!(backend.pixmap.pixels().iter().any(|p| p.alpha() > 0));
+    }
+}
@@ -0,0 +1,294 @@
+use std::io;
+
+use crate::math::Vec2;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VerticalAnchor {
+    Top,
+    Centre,
+    Bottom
+}
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HorizontalAnchor {
+    Start,
+    Centre,
+    End
+}
+
+/// 文字の大きさ (pt) と揃え方
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextSetting {
+    pub font_size: u32,
+    pub v_anchor : Option<VerticalAnchor>,
+    pub h_anchor : Option<HorizontalAnchor>,
+}
+impl TextSetting {
+    /// 用紙の単位での文字の大きさ。SVGと同じく1単位を1px、1ptを4/3pxとする
+    pub fn size(&self) -> f32 {
+        self.font_size as f32 * 4_f32 / 3_f32
+    }
+}
+
+/// 8bitのRGB色
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+impl Rgb {
+    pub const BLACK:Rgb = Rgb { r: 0, g: 0, b: 0 };
+
+    /// SVGの色指定 (`#rgb`, `#rrggbb`, 基本の色名) を読む。`none`や読めない指定は`None`
+    pub fn parse(colour:&str) -> Option<Rgb> {
+        let colour = colour.trim();
+        if let Some(hex) = colour.strip_prefix('#') {
+            let digits = hex.chars()
+                .map(|c| c.to_digit(16).map(|d| d as u8))
+                .collect::<Option<Vec<u8>>>()?;
+            return match digits.as_slice() {
+                [r, g, b] => Some(Rgb { r: r * 17, g: g * 17, b: b * 17 }),
+                [r1, r2, g1, g2, b1, b2] => Some(Rgb { r: r1 * 16 + r2, g: g1 * 16 + g2, b: b1 * 16 + b2 }),
+                _ => None
+            };
+        }
+        let (r, g, b) = match colour.to_ascii_lowercase().as_str() {
+            "black" => (0, 0, 0),
+            "white" => (255, 255, 255),
+            "red" => (255, 0, 0),
+            "lime" => (0, 255, 0),
+            "green" => (0, 128, 0),
+            "blue" => (0, 0, 255),
+            "yellow" => (255, 255, 0),
+            "cyan" | "aqua" => (0, 255, 255),
+            "magenta" | "fuchsia" => (255, 0, 255),
+            "gray" | "grey" => (128, 128, 128),
+            "silver" => (192, 192, 192),
+            "lightgray" | "lightgrey" => (211, 211, 211),
+            "darkgray" | "darkgrey" => (169, 169, 169),
+            "maroon" => (128, 0, 0),
+            "olive" => (128, 128, 0),
+            "navy" => (0, 0, 128),
+            "purple" => (128, 0, 128),
+            "teal" => (0, 128, 128),
+            "orange" => (255, 165, 0),
+            "brown" => (165, 42, 42),
+            "pink" => (255, 192, 203),
+            _ => return None
+        };
+        Some(Rgb { r, g, b })
+    }
+    /// 0から1の成分
+    pub fn to_unit(self) -> [f32; 3] {
+        [self.r as f32 / 255_f32, self.g as f32 / 255_f32, self.b as f32 / 255_f32]
+    }
+}
+
+/// 線と塗りの指定。色はSVGの色指定で、`None`は各出力形式の既定値に任せる
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Style {
+    // 線の色 / 太さ
+    pub stroke: Option<String>,
+    pub stroke_width: Option<f32>,
+    // 塗りの色 / 不透明度
+    pub fill: Option<String>,
+    pub fill_opacity: Option<f32>,
+}
+impl Style {
+    /// 塗りのない線
+    pub fn stroke(colour:&str, width:f32) -> Self {
+        Style {
+            stroke: Some(colour.to_string()),
+            stroke_width: Some(width),
+            fill: Some("none".to_string()),
+            fill_opacity: None
+        }
+    }
+    /// 線のない塗り
+    pub fn fill(colour:&str) -> Self {
+        Style {
+            stroke: Some("none".to_string()),
+            stroke_width: None,
+            fill: Some(colour.to_string()),
+            fill_opacity: None
+        }
+    }
+    pub fn with_fill(mut self, colour:&str) -> Self {
+        self.fill = Some(colour.to_string());
+        self
+    }
+    pub fn with_fill_opacity(mut self, opacity:f32) -> Self {
+        self.fill_opacity = Some(opacity);
+        self
+    }
+    /// 塗りの色。SVGと同じく指定がなければ黒で塗る
+    pub fn fill_colour(&self) -> Option<Rgb> {
+        match &self.fill {
+            Some(s) => Rgb::parse(s),
+            None => Some(Rgb::BLACK)
+        }
+    }
+    /// 線の色。指定がなければ線を引かない
+    pub fn stroke_colour(&self) -> Option<Rgb> {
+        self.stroke.as_deref().and_then(Rgb::parse)
+    }
+    /// 線の太さ。指定がなければ1
+    pub fn width(&self) -> f32 {
+        self.stroke_width.unwrap_or(1_f32)
+    }
+}
+
+/// パスの描画命令
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PathCommand {
+    MoveTo(Vec2),
+    LineTo(Vec2),
+    Close
+}
+impl PathCommand {
+    /// 点を順に結ぶ命令列。`closed`なら最後に始点へ戻る
+    pub fn through(points:&[Vec2], closed:bool) -> Vec<PathCommand> {
+        let mut commands = points.iter()
+            .enumerate()
+            .map(|(i, p)| if i == 0 { PathCommand::MoveTo(*p) } else { PathCommand::LineTo(*p) })
+            .collect::<Vec<PathCommand>>();
+        if closed && !commands.is_empty() {
+            commands.push(PathCommand::Close);
+        }
+        commands
+    }
+    /// 長方形の輪郭
+    pub fn rect(origin:Vec2, size:Vec2) -> Vec<PathCommand> {
+        PathCommand::through(&[
+            origin,
+            origin + Vec2::vec2(size.x, 0_f32),
+            origin + size,
+            origin + Vec2::vec2(0_f32, size.y)
+        ], true)
+    }
+}
+
+/// 文字列の中の1区切り。`dx` / `dy`は直前の文字からのずれ
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tspan {
+    pub text: String,
+    pub dx: Option<f32>,
+    pub dy: Option<f32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Text {
+    pub anchor: Vec2,
+    pub setting: TextSetting,
+    pub spans: Vec<Tspan>,
+}
+impl Text {
+    /// 1区切りだけの文字列
+    pub fn new(anchor:Vec2, text:String, setting:TextSetting) -> Self {
+        Text {
+            anchor,
+            setting,
+            spans: vec![Tspan { text, dx: None, dy: None }]
+        }
+    }
+    /// 区切りを繋げた文字列
+    pub fn content(&self) -> String {
+        self.spans.iter().map(|s| s.text.as_str()).collect()
+    }
+}
+
+/// 描画要素のまとまり。`id`と`class`で層を区別し、`clip`の内側だけを描く
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Group {
+    pub id: Option<String>,
+    pub class: Option<String>,
+    pub clip: Option<Vec<PathCommand>>,
+    pub children: Vec<Element>,
+}
+impl Group {
+    pub fn new(id:&str) -> Self {
+        Group { id: Some(id.to_string()), ..Default::default() }
+    }
+    pub fn with_class(mut self, class:&str) -> Self {
+        self.class = Some(class.to_string());
+        self
+    }
+    pub fn with_clip(mut self, clip:Vec<PathCommand>) -> Self {
+        self.clip = Some(clip);
+        self
+    }
+    pub fn with_children(mut self, children:Vec<Element>) -> Self {
+        self.children = children;
+        self
+    }
+}
+
+/// 軸や系列が作る、出力形式に依らない描画要素
+#[derive(Clone, Debug, PartialEq)]
+pub enum Element {
+    Group(Group),
+    Line { from: Vec2, to: Vec2, style: Style },
+    Polyline { points: Vec<Vec2>, closed: bool, style: Style },
+    Path { commands: Vec<PathCommand>, style: Style },
+    // `origin`は左上の角
+    Rect { origin: Vec2, size: Vec2, style: Style },
+    Circle { centre: Vec2, radius: f32, style: Style },
+    Text(Text),
+    // 描かずに参照される定義 (SVGの`<defs>`)
+    Defs(Vec<Element>),
+    // `id`で参照する切り抜きの輪郭
+    ClipPath { id: String, commands: Vec<PathCommand> },
+}
+
+/// 描画の出力先。座標は用紙の左上を原点とし、下向きをyの正とする
+pub trait Backend {
+    /// まとまりを開く。`end_group`までの描画が子要素になる
+    fn begin_group(&mut self, id:Option<&str>, class:Option<&str>) -> io::Result<()>;
+    fn end_group(&mut self) -> io::Result<()>;
+    /// 今のまとまりを閉じるまで、`commands`の内側だけを描く
+    fn clip(&mut self, commands:&[PathCommand]) -> io::Result<()>;
+    fn line(&mut self, from:Vec2, to:Vec2, style:&Style) -> io::Result<()>;
+    fn polyline(&mut self, points:&[Vec2], closed:bool, style:&Style) -> io::Result<()>;
+    fn path(&mut self, commands:&[PathCommand], style:&Style) -> io::Result<()>;
+    fn rect(&mut self, origin:Vec2, size:Vec2, style:&Style) -> io::Result<()>;
+    fn circle(&mut self, centre:Vec2, radius:f32, style:&Style) -> io::Result<()>;
+    fn text(&mut self, text:&Text) -> io::Result<()>;
+    /// 参照される定義を書く。既定では参照の仕組みがないので何も書かない
+    fn defs(&mut self, _elements:&[Element]) -> io::Result<()> {
+        Ok(())
+    }
+    /// 切り抜きの輪郭を定義する。既定では`clip`で直に切り抜くので何も書かない
+    fn clip_path(&mut self, _id:&str, _commands:&[PathCommand]) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// 描画要素を対応する命令に振り分ける
+    fn draw(&mut self, element:&Element) -> io::Result<()> {
+        match element {
+            Element::Group(group) => {
+                self.begin_group(group.id.as_deref(), group.class.as_deref())?;
+                if let Some(clip) = &group.clip {
+                    self.clip(clip)?;
+                }
+                for child in &group.children {
+                    self.draw(child)?;
+                }
+                self.end_group()
+            },
+            Element::Line { from, to, style } => self.line(*from, *to, style),
+            Element::Polyline { points, closed, style } => self.polyline(points, *closed, style),
+            Element::Path { commands, style } => self.path(commands, style),
+            Element::Rect { origin, size, style } => self.rect(*origin, *size, style),
+            Element::Circle { centre, radius, style } => self.circle(*centre, *radius, style),
+            Element::Text(text) => self.text(text),
+            Element::Defs(elements) => self.defs(elements),
+            Element::ClipPath { id, commands } => self.clip_path(id, commands)
+        }
+    }
+    fn draw_all(&mut self, elements:&[Element]) -> io::Result<()> {
+        for element in elements {
+            self.draw(element)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+mod bar;
+mod histogram;
+mod boxplot;
+mod fill;
+
+use crate::error::Error;
+use crate::graph_paper::{GraphPaper, XScale, YScale};
+use crate::math::Vec2;
+use crate::render::{Element, Style};
+
+pub use self::bar::{
+    BarChart,
+    BarSeries,
+    BarLayout
+};
+
+pub use self::histogram::{
+    Histogram,
+    Binning,
+    Normalisation
+};
+
+pub use self::boxplot::{
+    BoxPlot,
+    BoxStatistics
+};
+
+pub use self::fill::{
+    FillBetween,
+    FillBase,
+    Hatch
+};
+
+/// マウスを重ねたときに値を示す点
+#[derive(Clone, Debug, PartialEq)]
+pub struct DataPoint {
+    // 系列の名前。空なら出力側で番号から名付ける
+    pub name: String,
+    // 値の説明 (箱ひげ図の統計量など)。空なら示さない
+    pub label: String,
+    // 点を置く位置 (軸の値)
+    pub position: Vec2,
+    // 示す値
+    pub value: Vec2,
+}
+
+/// 点の散布図以外にグラフへ描く系列
+pub trait Series {
+    fn get_plot(&self, graph_paper:&GraphPaper, x_scale:&dyn XScale, y_scale:&dyn YScale) -> Vec<Element>;
+    /// 値を示す点。既定では示さない
+    fn get_data_points(&self) -> Vec<DataPoint> {
+        Vec::new()
+    }
+    /// 軸に対して描けるか確かめる。既定では常に描ける
+    fn validate(&self, _graph_paper:&GraphPaper, _x_scale:&dyn XScale, _y_scale:&dyn YScale) -> Result<(), Error> {
+        Ok(())
+    }
+    /// 設定ファイルに書く内容。既定では書けない
+    #[cfg(feature = "spec")]
+    fn spec(&self) -> Option<crate::spec::SeriesSpec> {
+        None
+    }
+}
+
+/// 対角の2点から塗り潰した長方形を作る
+fn get_rect(graph_paper:&GraphPaper, from:Vec2, to:Vec2, fill:&str) -> Element {
+    Element::Rect {
+        origin: Vec2::vec2(from.x.min(to.x), from.y.min(to.y)),
+        size: Vec2::vec2((to.x - from.x).abs(), (to.y - from.y).abs()),
+        style: Style::stroke("black", graph_paper.stroke_width).with_fill(fill)
+    }
+}
+
+/// 点を順に結んだ折れ線を作る
+fn get_polyline(graph_paper:&GraphPaper, points:&[Vec2], stroke:&str) -> Element {
+    Element::Polyline {
+        points: points.to_vec(),
+        closed: false,
+        style: Style::stroke(stroke, graph_paper.stroke_width)
+    }
+}
+
+/// 点を順に結んで閉じた多角形を作る
+fn get_polygon(graph_paper:&GraphPaper, points:&[Vec2], fill:&str) -> Element {
+    Element::Polyline {
+        points: points.to_vec(),
+        closed: true,
+        style: Style::stroke("black", graph_paper.stroke_width).with_fill(fill)
+    }
+}
+
+/// 中抜きの円を作る
+fn get_circle(graph_paper:&GraphPaper, centre:Vec2, radius:f32) -> Element {
+    Element::Circle {
+        centre,
+        radius,
+        style: Style::stroke("black", graph_paper.stroke_width)
+    }
+}
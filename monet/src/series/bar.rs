@@ -0,0 +1,151 @@
+use crate::graph_paper::{GraphPaper, XScale, YScale};
+use crate::math::Vec2;
+use crate::render::Element;
+
+use super::{DataPoint, Series, get_rect};
+
+/// 複数の系列の棒の並べ方
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum BarLayout {
+    // カテゴリの中で横に並べる
+    Grouped,
+    // カテゴリの中で積み上げる
+    Stacked
+}
+
+/// 棒グラフの1系列。`values[i]`が`i`番目のカテゴリの値
+#[derive(Clone)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize))]
+pub struct BarSeries {
+    pub name: String,
+    pub values: Vec<f32>,
+    // 塗り潰しの色 (SVGの色指定)
+    pub fill: String,
+}
+
+/// カテゴリ軸の上に描く棒グラフ
+#[derive(Clone)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize))]
+pub struct BarChart {
+    pub series: Vec<BarSeries>,
+    pub layout: BarLayout,
+    // カテゴリの幅に対する棒の幅
+    pub width: f32,
+}
+
+impl BarChart {
+    /// 積み上げたときの、系列ごと・カテゴリごとの (根元, 先端)。
+    /// 正の値は0から上へ、負の値は0から下へ別々に積む
+    fn stacks(&self) -> Vec<Vec<(f32, f32)>> {
+        let mut positive:Vec<f32> = Vec::new();
+        let mut negative:Vec<f32> = Vec::new();
+        let mut res = Vec::new();
+        for series in &self.series {
+            let mut bars = Vec::new();
+            for (i, value) in series.values.iter().enumerate() {
+                if positive.len() <= i {
+                    positive.resize(i + 1, 0_f32);
+                    negative.resize(i + 1, 0_f32);
+                }
+                let stack = if *value < 0_f32 { &mut negative[i] } else { &mut positive[i] };
+                let bottom = *stack;
+                *stack += value;
+                bars.push((bottom, *stack));
+            }
+            res.push(bars);
+        }
+        res
+    }
+}
+
+impl Series for BarChart {
+    fn get_plot(&self, graph_paper:&GraphPaper, x_scale:&dyn XScale, y_scale:&dyn YScale) -> Vec<Element> {
+        let x = x_scale.to_scaled_x(graph_paper);
+        let y = y_scale.to_scaled_y(graph_paper);
+        // 棒の根元。対数軸なら軸の下端から伸ばす
+        let (lo, hi) = y_scale.get_y_range();
+        let base = 0_f32.max(lo.min(hi));
+        let slot = self.width / self.series.len().max(1) as f32;
+        let stacks = self.stacks();
+        let mut res = Vec::new();
+        for (s, series) in self.series.iter().enumerate() {
+            for (i, value) in series.values.iter().enumerate() {
+                let centre = i as f32;
+                let (left, right, bottom, top) = match self.layout {
+                    BarLayout::Grouped => {
+                        let left = centre - self.width / 2_f32 + slot * s as f32;
+                        (left, left + slot, base, *value)
+                    },
+                    BarLayout::Stacked => {
+                        let (bottom, top) = stacks[s][i];
+                        // 正の棒の根元だけを軸の下端に揃える
+                        let bottom = if *value < 0_f32 { bottom } else { bottom.max(base) };
+                        (centre - self.width / 2_f32, centre + self.width / 2_f32, bottom, top)
+                    }
+                };
+                res.push(get_rect(
+                    graph_paper,
+                    Vec2::vec2(x(left), y(bottom)),
+                    Vec2::vec2(x(right), y(top)),
+                    &series.fill
+                ));
+            }
+        }
+        res
+    }
+    /// 棒の上端の中央に、カテゴリの番号と値を示す
+    fn get_data_points(&self) -> Vec<DataPoint> {
+        let slot = self.width / self.series.len().max(1) as f32;
+        let stacks = self.stacks();
+        let mut res = Vec::new();
+        for (s, series) in self.series.iter().enumerate() {
+            for (i, value) in series.values.iter().enumerate() {
+                let centre = i as f32;
+                let position = match self.layout {
+                    BarLayout::Grouped => Vec2::vec2(
+                        centre - self.width / 2_f32 + slot * (s as f32 + 0.5_f32),
+                        *value
+                    ),
+                    BarLayout::Stacked => Vec2::vec2(centre, stacks[s][i].1)
+                };
+                res.push(DataPoint {
+                    name: series.name.clone(),
+                    label: String::new(),
+                    position,
+                    value: Vec2::vec2(centre, *value)
+                });
+            }
+        }
+        res
+    }
+    #[cfg(feature = "spec")]
+    fn spec(&self) -> Option<crate::spec::SeriesSpec> {
+        Some(crate::spec::SeriesSpec::Bar(self.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negative_values_stack_below_zero() {
+        let chart = BarChart {
+            series: vec![
+                BarSeries { name: "a".to_string(), values: vec![2_f32, -1_f32], fill: "red".to_string() },
+                BarSeries { name: "b".to_string(), values: vec![-3_f32, -2_f32], fill: "blue".to_string() },
+                BarSeries { name: "c".to_string(), values: vec![1_f32, 4_f32], fill: "green".to_string() },
+            ],
+            layout: BarLayout::Stacked,
+            width: 0.8
+        };
+        assert_eq!(chart.stacks(), [
+            vec![(0_f32, 2_f32), (0_f32, -1_f32)],
+            vec![(0_f32, -3_f32), (-1_f32, -3_f32)],
+            vec![(2_f32, 3_f32), (0_f32, 4_f32)],
+        ]);
+        let tops = chart.get_data_points().iter().map(|p| p.position.y).collect::<Vec<f32>>();
+        assert_eq!(tops, [2_f32, -1_f32, -3_f32, -3_f32, 3_f32, 4_f32]);
+    }
+}
@@ -0,0 +1,231 @@
+use crate::graph_paper::{GraphPaper, XScale, YScale};
+use crate::math::Vec2;
+use crate::render::Element;
+use crate::math::statistics::{
+    sorted,
+    quantile,
+    silverman_bandwidth,
+    kernel_density
+};
+
+use super::{DataPoint, Series, get_rect, get_circle, get_polygon};
+
+// バイオリンの輪郭を近似する分割数
+const VIOLIN_RESOLUTION:u32 = 100;
+
+/// 箱ひげ図の要約統計量
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoxStatistics {
+    pub lower_quartile: f32,
+    pub median: f32,
+    pub upper_quartile: f32,
+    // 四分位範囲の1.5倍以内にある最も外側の値
+    pub lower_whisker: f32,
+    pub upper_whisker: f32,
+    pub outliers: Vec<f32>,
+}
+impl BoxStatistics {
+    pub fn new(values:&[f32]) -> Option<Self> {
+        let sorted = sorted(values);
+        if sorted.is_empty() {
+            return None;
+        }
+        let lower_quartile = quantile(&sorted, 0.25);
+        let upper_quartile = quantile(&sorted, 0.75);
+        let fence = 1.5 * (upper_quartile - lower_quartile);
+        let inside = |v:&&f32| lower_quartile - fence <= **v && **v <= upper_quartile + fence;
+        Some(BoxStatistics {
+            lower_quartile,
+            median: quantile(&sorted, 0.5),
+            upper_quartile,
+            lower_whisker: *sorted.iter().find(inside)?,
+            upper_whisker: *sorted.iter().rev().find(inside)?,
+            outliers: sorted.iter()
+                .filter(|v| !inside(v))
+                .copied()
+                .collect()
+        })
+    }
+}
+
+/// カテゴリ軸の上に描く箱ひげ図。`groups[i]`が`i`番目のカテゴリの値
+#[derive(Clone)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize))]
+pub struct BoxPlot {
+    // 系列の名前
+    #[cfg_attr(feature = "spec", serde(default))]
+    pub name: String,
+    pub groups: Vec<Vec<f32>>,
+    // カテゴリの幅に対する箱の幅
+    pub width: f32,
+    // 塗り潰しの色 (SVGの色指定)
+    pub fill: String,
+    // 箱の代わりにカーネル密度推定の輪郭を描く
+    pub violin: bool,
+}
+impl BoxPlot {
+    /// (カテゴリ, 値) の組をカテゴリごとに分ける。カテゴリは出現順に並ぶ
+    pub fn group<'a, I>(pairs:I) -> (Vec<String>, Vec<Vec<f32>>)
+        where I: IntoIterator<Item = (&'a str, f32)>
+    {
+        let mut categories:Vec<String> = Vec::new();
+        let mut groups:Vec<Vec<f32>> = Vec::new();
+        for (label, value) in pairs {
+            match categories.iter().position(|c| c == label) {
+                Some(i) => groups[i].push(value),
+                None => {
+                    categories.push(label.to_string());
+                    groups.push(vec![value]);
+                }
+            }
+        }
+        (categories, groups)
+    }
+
+    fn get_violin<F, G>(&self, graph_paper:&GraphPaper, centre:f32, values:&[f32], x:F, y:G) -> Option<Element>
+        where F: Fn(f32) -> f32, G: Fn(f32) -> f32
+    {
+        let sorted = sorted(values);
+        let bandwidth = silverman_bandwidth(&sorted);
+        if !bandwidth.is_finite() || bandwidth <= 0_f32 {
+            return None;
+        }
+        // 裾が途切れないよう両端をバンド幅の3倍だけ延ばす
+        let (lo, hi) = (sorted[0] - 3_f32 * bandwidth, sorted[sorted.len() - 1] + 3_f32 * bandwidth);
+        let profile = (0..VIOLIN_RESOLUTION + 1)
+            .map(|i| {
+                let v = lo + (hi - lo) * i as f32 / VIOLIN_RESOLUTION as f32;
+                (v, kernel_density(&sorted, v, bandwidth))
+            })
+            .collect::<Vec<(f32, f32)>>();
+        let peak = profile.iter().map(|(_, d)| *d).fold(0_f32, f32::max);
+        let half = |d:f32| d / peak * self.width / 2_f32;
+        let right = profile.iter().map(|(v, d)| Vec2::vec2(x(centre + half(*d)), y(*v)));
+        let left = profile.iter().rev().map(|(v, d)| Vec2::vec2(x(centre - half(*d)), y(*v)));
+        let outline = right.chain(left)
+            .filter(|p| p.x.is_finite() && p.y.is_finite())
+            .collect::<Vec<Vec2>>();
+        Some(get_polygon(graph_paper, &outline, &self.fill))
+    }
+}
+
+impl Series for BoxPlot {
+    fn get_plot(&self, graph_paper:&GraphPaper, x_scale:&dyn XScale, y_scale:&dyn YScale) -> Vec<Element> {
+        let x = x_scale.to_scaled_x(graph_paper);
+        let y = y_scale.to_scaled_y(graph_paper);
+        let mut res = Vec::new();
+        for (i, values) in self.groups.iter().enumerate() {
+            let Some(statistics) = BoxStatistics::new(values) else {
+                continue;
+            };
+            let centre = i as f32;
+            // バイオリンの中には細い箱を描く
+            let half = if self.violin {
+                res.extend(self.get_violin(graph_paper, centre, values, &x, &y));
+                self.width / 10_f32
+            } else {
+                self.width / 2_f32
+            };
+            let (left, right) = (x(centre - half), x(centre + half));
+            let middle = x(centre);
+            // ひげ
+            for (from, to) in [
+                (statistics.lower_whisker, statistics.lower_quartile),
+                (statistics.upper_quartile, statistics.upper_whisker)
+            ] {
+                res.push(graph_paper.get_line(Vec2::vec2(middle, y(from)), Vec2::vec2(middle, y(to))));
+            }
+            if !self.violin {
+                for whisker in [statistics.lower_whisker, statistics.upper_whisker] {
+                    let (l, r) = (x(centre - half / 2_f32), x(centre + half / 2_f32));
+                    res.push(graph_paper.get_line(Vec2::vec2(l, y(whisker)), Vec2::vec2(r, y(whisker))));
+                }
+            }
+            // 箱と中央値
+            res.push(get_rect(
+                graph_paper,
+                Vec2::vec2(left, y(statistics.lower_quartile)),
+                Vec2::vec2(right, y(statistics.upper_quartile)),
+                if self.violin { "white" } else { &self.fill }
+            ));
+            res.push(graph_paper.get_line(
+                Vec2::vec2(left, y(statistics.median)),
+                Vec2::vec2(right, y(statistics.median))
+            ));
+            // 外れ値
+            res.extend(statistics.outliers.iter().map(|v| {
+                get_circle(graph_paper, Vec2::vec2(middle, y(*v)), graph_paper.stroke_width * 2_f32)
+            }));
+        }
+        res
+    }
+    /// カテゴリごとの要約統計量と外れ値。バイオリンでも中の箱の値を示す
+    fn get_data_points(&self) -> Vec<DataPoint> {
+        let mut res = Vec::new();
+        for (i, values) in self.groups.iter().enumerate() {
+            let Some(statistics) = BoxStatistics::new(values) else {
+                continue;
+            };
+            let summary = [
+                ("lower whisker", statistics.lower_whisker),
+                ("lower quartile", statistics.lower_quartile),
+                ("median", statistics.median),
+                ("upper quartile", statistics.upper_quartile),
+                ("upper whisker", statistics.upper_whisker),
+            ];
+            let outliers = statistics.outliers.iter().map(|v| ("outlier", *v));
+            for (label, value) in summary.into_iter().chain(outliers) {
+                let point = Vec2::vec2(i as f32, value);
+                res.push(DataPoint { name: self.name.clone(), label: label.to_string(), position: point, value: point });
+            }
+        }
+        res
+    }
+    #[cfg(feature = "spec")]
+    fn spec(&self) -> Option<crate::spec::SeriesSpec> {
+        Some(crate::spec::SeriesSpec::BoxPlot(self.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statistics_with_outliers() {
+        let stats = BoxStatistics::new(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 30.0]).unwrap();
+        assert_eq!(stats.lower_quartile, 3.25);
+        assert_eq!(stats.median, 5.5);
+        assert_eq!(stats.upper_quartile, 7.75);
+        // 柵は 3.25 - 6.75 と 7.75 + 6.75
+        assert_eq!(stats.lower_whisker, 1_f32);
+        assert_eq!(stats.upper_whisker, 9_f32);
+        assert_eq!(stats.outliers, [30_f32]);
+    }
+
+    #[test]
+    fn data_points_per_group() {
+        let plot = BoxPlot {
+            name: "weight".to_string(),
+            groups: vec![vec![], vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 30.0]],
+            width: 0.5,
+            fill: "white".to_string(),
+            violin: true
+        };
+        let points = plot.get_data_points();
+        let labels = points.iter().map(|p| p.label.as_str()).collect::<Vec<&str>>();
+        assert_eq!(labels, ["lower whisker", "lower quartile", "median", "upper quartile", "upper whisker", "outlier"]);
+        assert!(points.iter().all(|p| p.name == "weight"));
+        assert!(points.iter().all(|p| p.position.x == 1_f32));
+        assert_eq!(points[2].value.y, 5.5);
+        assert_eq!(points[5].value.y, 30_f32);
+    }
+
+    #[test]
+    fn statistics_of_equal_values() {
+        let stats = BoxStatistics::new(&[2.0, 2.0, f32::NAN]).unwrap();
+        assert_eq!((stats.lower_whisker, stats.median, stats.upper_whisker), (2.0, 2.0, 2.0));
+        assert!(stats.outliers.is_empty());
+        assert_eq!(BoxStatistics::new(&[]), None);
+    }
+}
@@ -0,0 +1,185 @@
+use crate::error::Error;
+use crate::graph_paper::{GraphPaper, XScale, YScale};
+use crate::math::Vec2;
+use crate::render::{Element, Group, PathCommand, Style};
+
+use super::{DataPoint, Series};
+
+/// 塗り潰しの下側の境界
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum FillBase {
+    // 一定の値 (曲線の下の面積など)
+    Baseline(f32),
+    // もう一本の曲線 (信頼区間など)
+    Curve(Vec<Vec2>)
+}
+
+/// 斜線の本数の上限。間隔が狭すぎるときは広げる
+const MAX_HATCH_LINES:f32 = 1000_f32;
+
+/// 白黒印刷向けの斜線模様
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hatch {
+    // 線の角度 (度)
+    pub angle: f32,
+    // 線の間隔
+    pub spacing: f32,
+    // 線の色 (SVGの色指定)
+    pub colour: String,
+}
+impl Hatch {
+    /// 輪郭で切り抜いた平行線。線は輪郭を囲む円を覆うように引く
+    fn get_lines(&self, graph_paper:&GraphPaper, outline:&[Vec2]) -> Element {
+        let (lo, hi) = outline.iter().fold(
+            (Vec2::vec2(f32::MAX, f32::MAX), Vec2::vec2(f32::MIN, f32::MIN)),
+            |(lo, hi), p| (Vec2::vec2(lo.x.min(p.x), lo.y.min(p.y)), Vec2::vec2(hi.x.max(p.x), hi.y.max(p.y)))
+        );
+        let centre = (lo + hi).mul(0.5);
+        let radius = ((hi.x - lo.x).powi(2) + (hi.y - lo.y).powi(2)).sqrt() / 2_f32;
+        // 角度0で縦線となり、時計回りに傾ける
+        let angle = self.angle.to_radians();
+        let direction = Vec2::vec2(-angle.sin(), angle.cos());
+        let normal = Vec2::vec2(angle.cos(), angle.sin());
+        let spacing = self.spacing.max(2_f32 * radius / MAX_HATCH_LINES);
+        // 線の位置を原点からの間隔の倍数に揃える
+        let offset = centre.x * normal.x + centre.y * normal.y;
+        let first = ((offset - radius) / spacing).ceil() as i64;
+        let last = ((offset + radius) / spacing).floor() as i64;
+        let lines = (first..last + 1)
+            .map(|k| {
+                let foot = centre + normal.mul(k as f32 * spacing - offset);
+                Element::Line {
+                    from: foot - direction.mul(radius),
+                    to: foot + direction.mul(radius),
+                    style: Style::stroke(&self.colour, graph_paper.stroke_width)
+                }
+            })
+            .collect::<Vec<Element>>();
+        Element::Group(Group {
+            class: Some("hatch".to_string()),
+            clip: Some(PathCommand::through(outline, true)),
+            children: lines,
+            ..Default::default()
+        })
+    }
+}
+
+/// 曲線と基準線、または2本の曲線の間を塗り潰す系列
+#[derive(Clone)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize))]
+pub struct FillBetween {
+    // 上側の曲線 (x の昇順)
+    pub curve: Vec<Vec2>,
+    pub base: FillBase,
+    // 塗り潰しの色 (SVGの色指定) / 不透明度
+    pub fill: String,
+    pub opacity: f32,
+    pub hatch: Option<Hatch>,
+}
+
+impl FillBetween {
+    /// 輪郭の値。下側の境界は逆順に辿って輪郭を閉じる
+    fn get_outline(&self) -> Vec<Vec2> {
+        let (Some(first), Some(last)) = (self.curve.first(), self.curve.last()) else {
+            return Vec::new();
+        };
+        let base = match &self.base {
+            FillBase::Baseline(v) => vec![Vec2::vec2(last.x, *v), Vec2::vec2(first.x, *v)],
+            FillBase::Curve(curve) => curve.iter().rev().copied().collect()
+        };
+        self.curve.iter().chain(base.iter()).copied().collect()
+    }
+}
+
+impl Series for FillBetween {
+    fn get_plot(&self, graph_paper:&GraphPaper, x_scale:&dyn XScale, y_scale:&dyn YScale) -> Vec<Element> {
+        let x = x_scale.to_scaled_x(graph_paper);
+        let y = y_scale.to_scaled_y(graph_paper);
+        let outline = self.get_outline()
+            .into_iter()
+            .map(|p| Vec2::vec2(x(p.x), y(p.y)))
+            .collect::<Vec<Vec2>>();
+        // 写せない点を飛ばすと形が変わるので描かない
+        if outline.is_empty() || outline.iter().any(|p| !(p.x.is_finite() && p.y.is_finite())) {
+            return Vec::new();
+        }
+        let mut res = vec![Element::Path {
+            commands: PathCommand::through(&outline, true),
+            style: Style::fill(&self.fill).with_fill_opacity(self.opacity)
+        }];
+        if let Some(hatch) = &self.hatch && hatch.spacing > 0_f32 {
+            res.push(hatch.get_lines(graph_paper, &outline));
+        }
+        res
+    }
+    /// 輪郭の点がすべて用紙の位置に写せるか確かめる
+    fn validate(&self, graph_paper:&GraphPaper, x_scale:&dyn XScale, y_scale:&dyn YScale) -> Result<(), Error> {
+        let x = x_scale.to_scaled_x(graph_paper);
+        let y = y_scale.to_scaled_y(graph_paper);
+        match self.get_outline().into_iter().find(|p| !(x(p.x).is_finite() && y(p.y).is_finite())) {
+            Some(p) => Err(Error::UnmappablePoint(p)),
+            None => Ok(())
+        }
+    }
+    /// 上側の曲線の点
+    fn get_data_points(&self) -> Vec<DataPoint> {
+        self.curve.iter()
+            .map(|p| DataPoint { name: String::new(), label: String::new(), position: *p, value: *p })
+            .collect()
+    }
+    #[cfg(feature = "spec")]
+    fn spec(&self) -> Option<crate::spec::SeriesSpec> {
+        Some(crate::spec::SeriesSpec::Fill(self.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph_paper::{XLinearScale, YLinearScale, YLogScale};
+    use super::*;
+
+    fn graph_paper() -> GraphPaper {
+        GraphPaper {
+            name: String::new(),
+            margin: 100_f32,
+            size: Vec2::vec2(1100_f32, 1100_f32),
+            points: Vec::new(),
+            stroke_width: 1_f32,
+            great_split_length: 20_f32,
+            short_split_length: 10_f32
+        }
+    }
+
+    fn fill(base:f32, hatch:Option<Hatch>) -> FillBetween {
+        FillBetween {
+            curve: vec![Vec2::vec2(0_f32, 5_f32), Vec2::vec2(10_f32, 8_f32)],
+            base: FillBase::Baseline(base),
+            fill: "gray".to_string(),
+            opacity: 0.5,
+            hatch
+        }
+    }
+
+    #[test]
+    fn hatch_lines_are_bounded() {
+        let hatch = Hatch { angle: 45_f32, spacing: 1e-6, colour: "black".to_string() };
+        let x = XLinearScale { h_great_split: 5, h_short_split: 2, max_value: 10_f32, reversed: false };
+        let y = YLinearScale { v_great_split: 5, v_short_split: 2, max_value: 10_f32, reversed: false };
+        let plot = fill(0_f32, Some(hatch)).get_plot(&graph_paper(), &x, &y);
+        let Some(Element::Group(group)) = plot.last() else { panic!("no hatch") };
+        assert!(!group.children.is_empty());
+        assert!(group.children.len() <= MAX_HATCH_LINES as usize + 1, "{}", group.children.len());
+    }
+
+    #[test]
+    fn unmappable_outline_is_rejected() {
+        let x = XLinearScale { h_great_split: 5, h_short_split: 2, max_value: 10_f32, reversed: false };
+        let y = YLogScale { base: 10_f32, from: -1, to: 1, reversed: false };
+        // 対数軸に0の基準線は写せない
+        assert_eq!(fill(0_f32, None).validate(&graph_paper(), &x, &y), Err(Error::UnmappablePoint(Vec2::vec2(10_f32, 0_f32))));
+        assert!(fill(0_f32, None).get_plot(&graph_paper(), &x, &y).is_empty());
+        assert_eq!(fill(1_f32, None).validate(&graph_paper(), &x, &y), Ok(()));
+    }
+}
@@ -0,0 +1,254 @@
+use crate::graph_paper::{GraphPaper, XScale, YScale};
+use crate::math::Vec2;
+use crate::render::Element;
+use crate::math::statistics::{
+    sorted,
+    mean,
+    standard_deviation,
+    quantile,
+    normal_pdf
+};
+
+use super::{DataPoint, Series, get_rect, get_polyline};
+
+// 正規分布の曲線を近似する折れ線の分割数
+const PDF_RESOLUTION:u32 = 200;
+// 階級数の上限。これを超える細かさは階級幅を広げて抑える
+const MAX_BINS:usize = 10000;
+
+/// 階級の決め方
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum Binning {
+    // 階級幅を指定する
+    Width(f32),
+    // 階級数を指定する
+    Count(u32),
+    Sturges,
+    Scott,
+    FreedmanDiaconis
+}
+
+/// 棒の高さの表し方
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum Normalisation {
+    // 度数
+    Count,
+    // 面積の合計が1となる確率密度
+    Density
+}
+
+/// 値を階級に分けて度数を棒で描くヒストグラム
+#[derive(Clone)]
+#[cfg_attr(feature = "spec", derive(serde::Serialize, serde::Deserialize))]
+pub struct Histogram {
+    // 系列の名前
+    #[cfg_attr(feature = "spec", serde(default))]
+    pub name: String,
+    pub values: Vec<f32>,
+    pub binning: Binning,
+    pub normalisation: Normalisation,
+    // 塗り潰しの色 (SVGの色指定)
+    pub fill: String,
+    // 平均と標準偏差から求めた正規分布を重ねる
+    pub normal_pdf: bool,
+}
+impl Histogram {
+    /// 最初の階級の左端と階級幅、階級数
+    fn edges(&self, sorted:&[f32]) -> (f32, f32, usize) {
+        let (min, max) = (sorted[0], sorted[sorted.len() - 1]);
+        let n = sorted.len() as f32;
+        let sturges = || (max - min) / (n.log2().ceil() + 1_f32);
+        let width = match self.binning {
+            Binning::Width(width) => width,
+            Binning::Count(count) => (max - min) / count.max(1) as f32,
+            Binning::Sturges => sturges(),
+            Binning::Scott => 3.49 * standard_deviation(sorted) * n.powf(-1_f32 / 3_f32),
+            Binning::FreedmanDiaconis => {
+                let iqr = quantile(sorted, 0.75) - quantile(sorted, 0.25);
+                2_f32 * iqr * n.powf(-1_f32 / 3_f32)
+            }
+        };
+        // 値が揃っていて幅が決まらない場合はSturgesの公式、それでも駄目なら幅1とする
+        let width = [width, sturges(), 1_f32].into_iter()
+            .find(|w| w.is_finite() && *w > 0_f32)
+            .unwrap_or(1_f32);
+        // 幅の倍数に揃えると階級が1つ増えうるので、上限より1つ少なく見積もる
+        let width = width.max((max - min) / (MAX_BINS - 1) as f32);
+        let start = match self.binning {
+            // 幅を指定した場合は階級の境界を幅の倍数に揃える
+            Binning::Width(_) => (min / width).floor() * width,
+            _ => min
+        };
+        // 最大値は最後の階級の右端に含める
+        let count = (((max - start) / width - 1e-4).ceil() as usize).clamp(1, MAX_BINS);
+        (start, width, count)
+    }
+    /// 各階級の (左端, 右端, 高さ)
+    pub fn bins(&self) -> Vec<(f32, f32, f32)> {
+        let sorted = sorted(&self.values);
+        if sorted.is_empty() {
+            return Vec::new();
+        }
+        let (start, width, count) = self.edges(&sorted);
+        let mut counts = vec![0_u32; count];
+        for v in &sorted {
+            let i = (((v - start) / width).floor() as usize).min(count - 1);
+            counts[i] += 1;
+        }
+        let scale = match self.normalisation {
+            Normalisation::Count => 1_f32,
+            Normalisation::Density => 1_f32 / (sorted.len() as f32 * width)
+        };
+        counts.iter()
+            .enumerate()
+            .map(|(i, c)| {
+                let left = start + i as f32 * width;
+                (left, left + width, *c as f32 * scale)
+            })
+            .collect()
+    }
+}
+
+impl Series for Histogram {
+    fn get_plot(&self, graph_paper:&GraphPaper, x_scale:&dyn XScale, y_scale:&dyn YScale) -> Vec<Element> {
+        let x = x_scale.to_scaled_x(graph_paper);
+        let y = y_scale.to_scaled_y(graph_paper);
+        // 棒の根元。対数軸なら軸の下端から伸ばす
+        let (lo, hi) = y_scale.get_y_range();
+        let base = 0_f32.max(lo.min(hi));
+        let bins = self.bins();
+        let mut res = bins.iter()
+            .filter(|(_, _, height)| *height > base)
+            .map(|(left, right, height)| get_rect(
+                graph_paper,
+                Vec2::vec2(x(*left), y(base)),
+                Vec2::vec2(x(*right), y(*height)),
+                &self.fill
+            ))
+            .collect::<Vec<Element>>();
+        if self.normal_pdf && let (Some(first), Some(last)) = (bins.first(), bins.last()) {
+            let values = sorted(&self.values);
+            let (mean, sd) = (mean(&values), standard_deviation(&values));
+            // 度数で描くなら、曲線の面積を度数の合計に合わせる
+            let scale = match self.normalisation {
+                Normalisation::Count => values.len() as f32 * (first.1 - first.0),
+                Normalisation::Density => 1_f32
+            };
+            let points = (0..PDF_RESOLUTION + 1)
+                .map(|i| {
+                    let v = first.0 + (last.1 - first.0) * i as f32 / PDF_RESOLUTION as f32;
+                    Vec2::vec2(x(v), y(normal_pdf(v, mean, sd) * scale))
+                })
+                .filter(|p| p.x.is_finite() && p.y.is_finite())
+                .collect::<Vec<Vec2>>();
+            if sd > 0_f32 {
+                res.push(get_polyline(graph_paper, &points, "black"));
+            }
+        }
+        res
+    }
+    /// 棒の上端の中央。xは階級の中央の値
+    fn get_data_points(&self) -> Vec<DataPoint> {
+        self.bins()
+            .into_iter()
+            .filter(|(_, _, height)| *height > 0_f32)
+            .map(|(left, right, height)| {
+                let point = Vec2::vec2((left + right) / 2_f32, height);
+                DataPoint { name: self.name.clone(), label: String::new(), position: point, value: point }
+            })
+            .collect()
+    }
+    #[cfg(feature = "spec")]
+    fn spec(&self) -> Option<crate::spec::SeriesSpec> {
+        Some(crate::spec::SeriesSpec::Histogram(self.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn histogram(values:Vec<f32>, binning:Binning, normalisation:Normalisation) -> Histogram {
+        Histogram { name: "sample".to_string(), values, binning, normalisation, fill: "gray".to_string(), normal_pdf: false }
+    }
+
+    fn one_to_ten() -> Vec<f32> {
+        (1..=10).map(|v| v as f32).collect()
+    }
+
+    fn counts(binning:Binning) -> Vec<f32> {
+        histogram(one_to_ten(), binning, Normalisation::Count).bins()
+            .iter()
+            .map(|b| b.2)
+            .collect()
+    }
+
+    #[test]
+    fn width_aligns_to_multiples() {
+        let bins = histogram(one_to_ten(), Binning::Width(2_f32), Normalisation::Count).bins();
+        assert_eq!(bins.first().map(|b| b.0), Some(0_f32));
+        assert_eq!(bins.last().map(|b| b.1), Some(10_f32));
+        // 最大値の10は最後の階級に入る
+        assert_eq!(counts(Binning::Width(2_f32)), [1.0, 2.0, 2.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn count_splits_range() {
+        assert_eq!(counts(Binning::Count(3)), [3.0, 3.0, 4.0]);
+        assert_eq!(counts(Binning::Count(0)), [10.0]);
+    }
+
+    #[test]
+    fn rules_choose_bin_count() {
+        // Sturges: log2(10)を切り上げて1を足した5階級
+        assert_eq!(counts(Binning::Sturges).len(), 5);
+        // Scott: 3.49 * 3.03 * 10^(-1/3) ≒ 4.90
+        assert_eq!(counts(Binning::Scott).len(), 2);
+        // Freedman-Diaconis: 2 * 4.5 * 10^(-1/3) ≒ 4.18
+        assert_eq!(counts(Binning::FreedmanDiaconis).len(), 3);
+        for binning in [Binning::Sturges, Binning::Scott, Binning::FreedmanDiaconis] {
+            assert_eq!(counts(binning).iter().sum::<f32>(), 10_f32);
+        }
+    }
+
+    #[test]
+    fn equal_values_make_one_bin() {
+        for binning in [Binning::Count(4), Binning::Sturges, Binning::Scott, Binning::FreedmanDiaconis] {
+            let bins = histogram(vec![5_f32; 3], binning, Normalisation::Count).bins();
+            assert_eq!(bins, [(5_f32, 6_f32, 3_f32)]);
+        }
+        assert!(histogram(vec![f32::NAN], Binning::Sturges, Normalisation::Count).bins().is_empty());
+    }
+
+    #[test]
+    fn density_integrates_to_one() {
+        let values = (0..100).map(|i| (i as f32 * 0.37).sin() * 4_f32).collect::<Vec<f32>>();
+        for binning in [Binning::Width(0.3), Binning::Count(7), Binning::Sturges, Binning::FreedmanDiaconis] {
+            let area = histogram(values.clone(), binning, Normalisation::Density).bins()
+                .iter()
+                .map(|(left, right, height)| (right - left) * height)
+                .sum::<f32>();
+            assert!((area - 1_f32).abs() < 1e-4, "{:?}: {}", binning, area);
+        }
+    }
+
+    #[test]
+    fn data_points_mark_bin_tops() {
+        let points = histogram(one_to_ten(), Binning::Count(3), Normalisation::Count).get_data_points();
+        let values = points.iter().map(|p| p.value).collect::<Vec<Vec2>>();
+        assert_eq!(values, [Vec2::vec2(2.5, 3.0), Vec2::vec2(5.5, 3.0), Vec2::vec2(8.5, 4.0)]);
+        assert!(points.iter().all(|p| p.name == "sample"));
+    }
+
+    #[test]
+    fn bin_count_is_capped() {
+        for binning in [Binning::Width(1e-6), Binning::Count(u32::MAX)] {
+            let bins = histogram(vec![0_f32, 1000_f32], binning, Normalisation::Count).bins();
+            assert!(bins.len() <= MAX_BINS);
+            assert_eq!(bins.iter().map(|b| b.2).sum::<f32>(), 2_f32);
+            assert!(bins.last().is_some_and(|b| b.1 >= 1000_f32));
+        }
+    }
+}
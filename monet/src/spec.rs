@@ -0,0 +1,426 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::graph_paper::{Graph, GraphPaper, XScale, YScale};
+use crate::graph_paper::{XBrokenScale, YBrokenScale, XCategoryScale};
+use crate::graph_paper::{XLinearScale, YLinearScale, XLogScale, YLogScale};
+use crate::graph_paper::{XPowerScale, YPowerScale, XReciprocalScale, YReciprocalScale};
+use crate::graph_paper::{XSymlogScale, YSymlogScale, XTimeScale, YTimeScale};
+use crate::math::Vec2;
+use crate::math::calendar::parse_timestamp;
+use crate::series::{BarChart, BoxPlot, FillBetween, Histogram, Series};
+
+/// X軸の設定。関数で決まる軸 (関数軸、副軸) は書けない
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum XScaleSpec {
+    Linear(XLinearScale),
+    Log(XLogScale),
+    Symlog(XSymlogScale),
+    Reciprocal(XReciprocalScale),
+    Power(XPowerScale),
+    Broken(XBrokenScale),
+    Time(XTimeScale),
+    Category(XCategoryScale),
+}
+impl XScaleSpec {
+    pub fn to_scale(&self) -> Box<dyn XScale> {
+        match self {
+            XScaleSpec::Linear(scale) => Box::new(scale.clone()),
+            XScaleSpec::Log(scale) => Box::new(scale.clone()),
+            XScaleSpec::Symlog(scale) => Box::new(scale.clone()),
+            XScaleSpec::Reciprocal(scale) => Box::new(scale.clone()),
+            XScaleSpec::Power(scale) => Box::new(scale.clone()),
+            XScaleSpec::Broken(scale) => Box::new(scale.clone()),
+            XScaleSpec::Time(scale) => Box::new(scale.clone()),
+            XScaleSpec::Category(scale) => Box::new(scale.clone())
+        }
+    }
+    /// CSVの欄を点の値にする。時刻は軸の始点からの秒数、カテゴリは位置にする
+    fn parse(&self, field:&str) -> Option<f32> {
+        match self {
+            XScaleSpec::Time(scale) => parse_timestamp(field).map(|t| (t - scale.start) as f32),
+            XScaleSpec::Category(scale) => scale.index(field).map(|i| i as f32),
+            _ => field.trim().parse::<f64>().ok().map(|v| v as f32)
+        }
+    }
+}
+
+/// Y軸の設定。関数で決まる軸 (関数軸、副軸) は書けない
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum YScaleSpec {
+    Linear(YLinearScale),
+    Log(YLogScale),
+    Symlog(YSymlogScale),
+    Reciprocal(YReciprocalScale),
+    Power(YPowerScale),
+    Broken(YBrokenScale),
+    Time(YTimeScale),
+}
+impl YScaleSpec {
+    pub fn to_scale(&self) -> Box<dyn YScale> {
+        match self {
+            YScaleSpec::Linear(scale) => Box::new(scale.clone()),
+            YScaleSpec::Log(scale) => Box::new(scale.clone()),
+            YScaleSpec::Symlog(scale) => Box::new(scale.clone()),
+            YScaleSpec::Reciprocal(scale) => Box::new(scale.clone()),
+            YScaleSpec::Power(scale) => Box::new(scale.clone()),
+            YScaleSpec::Broken(scale) => Box::new(scale.clone()),
+            YScaleSpec::Time(scale) => Box::new(scale.clone())
+        }
+    }
+    /// CSVの欄を点の値にする。時刻は軸の始点からの秒数にする
+    fn parse(&self, field:&str) -> Option<f32> {
+        match self {
+            YScaleSpec::Time(scale) => parse_timestamp(field).map(|t| (t - scale.start) as f32),
+            _ => field.trim().parse::<f64>().ok().map(|v| v as f32)
+        }
+    }
+}
+
+/// 系列の設定
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SeriesSpec {
+    Bar(BarChart),
+    Histogram(Histogram),
+    BoxPlot(BoxPlot),
+    Fill(FillBetween),
+}
+impl SeriesSpec {
+    pub fn to_series(&self) -> Box<dyn Series> {
+        match self {
+            SeriesSpec::Bar(series) => Box::new(series.clone()),
+            SeriesSpec::Histogram(series) => Box::new(series.clone()),
+            SeriesSpec::BoxPlot(series) => Box::new(series.clone()),
+            SeriesSpec::Fill(series) => Box::new(series.clone())
+        }
+    }
+}
+
+/// プロット点を読むCSV。1行目は見出しとして読み飛ばす
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CsvSource {
+    // 相対パスは設定ファイルのあるディレクトリから辿る
+    pub path: PathBuf,
+    // (xの列, yの列) の組。列は0から数える
+    pub columns: Vec<(usize, usize)>,
+}
+impl CsvSource {
+    /// `path`が`base`の中にあれば、`base`からの相対パスで持つ
+    pub fn new(path:&Path, base:&Path, columns:Vec<(usize, usize)>) -> Self {
+        let path = match (path.canonicalize(), base.canonicalize()) {
+            (Ok(path), Ok(base)) => path.strip_prefix(&base).map(Path::to_path_buf).unwrap_or(path),
+            _ => path.to_path_buf()
+        };
+        CsvSource { path, columns }
+    }
+    fn read_points(&self, base:&Path, x_scale:&XScaleSpec, y_scale:&YScaleSpec) -> Result<Vec<Vec2>, Error> {
+        let path = base.join(&self.path);
+        let error = |e:csv::Error| Error::InvalidData(format!("{}: {}", path.display(), e));
+        let mut reader = csv::Reader::from_path(&path).map_err(error)?;
+        let records = reader.records().collect::<Result<Vec<_>, _>>().map_err(error)?;
+        let mut points = Vec::new();
+        for &(x, y) in &self.columns {
+            for record in &records {
+                let (Some(x), Some(y)) = (record.get(x), record.get(y)) else {
+                    return Err(Error::InvalidData(format!(
+                        "{}: the columns {} and {} don't exist", path.display(), x, y
+                    )));
+                };
+                // 読めない欄 (空欄など) は飛ばす
+                if let (Some(x), Some(y)) = (x_scale.parse(x), y_scale.parse(y)) {
+                    points.push(Vec2::vec2(x, y));
+                }
+            }
+        }
+        Ok(points)
+    }
+}
+
+/// 列ごとに読んだCSV。`columns[i]`が`i`番目の列の欄
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CsvColumns {
+    pub headers: Vec<String>,
+    pub columns: Vec<Vec<String>>,
+}
+impl CsvColumns {
+    /// 1行目を見出しとしてCSVを読む。見出しより多い欄は捨てる
+    pub fn read(path:&Path) -> Result<Self, Error> {
+        let error = |e:csv::Error| Error::InvalidData(format!("{}: {}", path.display(), e));
+        let mut reader = csv::Reader::from_path(path).map_err(error)?;
+        let headers = reader.headers()
+            .map_err(error)?
+            .iter()
+            .map(|h| h.to_string())
+            .collect::<Vec<String>>();
+        let mut columns = vec![Vec::new(); headers.len()];
+        for record in reader.records() {
+            for (column, field) in columns.iter_mut().zip(record.map_err(error)?.iter()) {
+                column.push(field.to_string());
+            }
+        }
+        Ok(CsvColumns { headers, columns })
+    }
+}
+
+/// 時間軸にする値の範囲 (最小, 最大)。値が1つに揃えば1秒、値がなければ0から1秒の幅にする
+pub fn time_span(values:impl Iterator<Item = f64>) -> (f64, f64) {
+    let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+        (min.min(v), max.max(v))
+    });
+    if min.is_finite() && max.is_finite() && min < max {
+        (min, max)
+    } else if min.is_finite() {
+        (min, min + 1_f64)
+    } else {
+        (0_f64, 1_f64)
+    }
+}
+
+/// グラフの設定ファイルの内容。TOMLかJSONで読み書きする
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GraphSpec {
+    pub graph_paper: GraphPaper,
+    // プロット点に加えるCSV
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub csv: Vec<CsvSource>,
+    pub x_scale: XScaleSpec,
+    pub y_scale: YScaleSpec,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub series: Vec<SeriesSpec>,
+}
+impl GraphSpec {
+    pub fn from_toml(text:&str) -> Result<Self, Error> {
+        toml::from_str(text).map_err(|e| Error::InvalidSpec(e.to_string()))
+    }
+    pub fn to_toml(&self) -> Result<String, Error> {
+        toml::to_string(self).map_err(|e| Error::InvalidSpec(e.to_string()))
+    }
+    pub fn from_json(text:&str) -> Result<Self, Error> {
+        serde_json::from_str(text).map_err(|e| Error::InvalidSpec(e.to_string()))
+    }
+    pub fn to_json(&self) -> Result<String, Error> {
+        serde_json::to_string_pretty(self).map_err(|e| Error::InvalidSpec(e.to_string()))
+    }
+    /// 拡張子が`.json`ならJSON、それ以外はTOMLとして読む
+    pub fn open(path:&Path) -> Result<Self, Error> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| Error::InvalidSpec(format!("{}: {}", path.display(), e)))?;
+        if is_json(path) { Self::from_json(&text) } else { Self::from_toml(&text) }
+    }
+    /// 拡張子が`.json`ならJSON、それ以外はTOMLとして書く
+    pub fn save(&self, path:&Path) -> Result<(), Error> {
+        let text = if is_json(path) { self.to_json()? } else { self.to_toml()? };
+        Ok(fs::write(path, text)?)
+    }
+    /// CSVを読んでグラフを作る。CSVの相対パスは`base`から辿る
+    pub fn to_graph(&self, base:&Path) -> Result<Graph, Error> {
+        let mut graph_paper = self.graph_paper.clone();
+        for source in &self.csv {
+            graph_paper.points.extend(source.read_points(base, &self.x_scale, &self.y_scale)?);
+        }
+        Ok(Graph {
+            graph_paper,
+            x_scale: self.x_scale.to_scale(),
+            y_scale: self.y_scale.to_scale(),
+            series: self.series.iter().map(SeriesSpec::to_series).collect()
+        })
+    }
+}
+
+fn is_json(path:&Path) -> bool {
+    path.extension().is_some_and(|e| e.eq_ignore_ascii_case("json"))
+}
+
+impl Graph {
+    /// 設定ファイルに書く内容。プロット点はそのまま書き込む
+    pub fn to_spec(&self) -> Result<GraphSpec, Error> {
+        Ok(GraphSpec {
+            graph_paper: self.graph_paper.clone(),
+            csv: Vec::new(),
+            x_scale: self.x_scale.spec().ok_or(Error::Unserialisable("x axis"))?,
+            y_scale: self.y_scale.spec().ok_or(Error::Unserialisable("y axis"))?,
+            series: self.series.iter()
+                .map(|s| s.spec().ok_or(Error::Unserialisable("series")))
+                .collect::<Result<_, _>>()?
+        })
+    }
+    /// 設定ファイルを読んでグラフを作る
+    pub fn open_spec(path:&Path) -> Result<Graph, Error> {
+        GraphSpec::open(path)?.to_graph(path.parent().unwrap_or(Path::new("")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph_paper::{XCategoryScale, XTimeScale, YTimeScale};
+    use crate::graph_paper::TickDomain;
+    use crate::series::{BarLayout, BarSeries, Binning, FillBase, Hatch, Normalisation};
+    use super::*;
+
+    fn graph_paper() -> GraphPaper {
+        GraphPaper {
+            name: "round trip".to_string(),
+            margin: 150_f32,
+            size: Vec2::vec2(2970_f32, 2100_f32),
+            points: vec![Vec2::vec2(0.1, 0.2), Vec2::vec2(3.5, 7.25)],
+            stroke_width: 2.5,
+            great_split_length: 30_f32,
+            short_split_length: 15_f32
+        }
+    }
+
+    fn x_scales() -> Vec<XScaleSpec> {
+        vec![
+            XScaleSpec::Linear(XLinearScale { h_great_split: 5, h_short_split: 2, max_value: 10_f32, reversed: false }),
+            XScaleSpec::Log(XLogScale { base: 10_f32, from: -1, to: 2, tick: 9, reversed: true }),
+            XScaleSpec::Symlog(XSymlogScale { base: 10_f32, threshold: 0.1, min_value: -100_f32, max_value: 100_f32, reversed: false }),
+            XScaleSpec::Reciprocal(XReciprocalScale {
+                min_value: 250_f32, max_value: 500_f32, factor: 1000_f32,
+                tick_domain: TickDomain::Reciprocal, h_great_split: 5, h_short_split: 2, reversed: false
+            }),
+            XScaleSpec::Power(XPowerScale {
+                exponent: 0.5, min_value: 0_f32, max_value: 100_f32, h_great_split: 10, h_short_split: 5, reversed: false
+            }),
+            XScaleSpec::Broken(XBrokenScale {
+                segments: vec![(0_f32, 10_f32), (90_f32, 100_f32)], gap: 0.05, h_great_split: 2, h_short_split: 5, reversed: false
+            }),
+            XScaleSpec::Time(XTimeScale {
+                start: 1714521600_f64, end: 1717200000_f64, h_great_split: 5, h_short_split: 2, reversed: false
+            }),
+            XScaleSpec::Category(XCategoryScale::from_labels(["A", "B \"quoted\"", "日本"])),
+        ]
+    }
+
+    fn y_scales() -> Vec<YScaleSpec> {
+        vec![
+            YScaleSpec::Linear(YLinearScale { v_great_split: 4, v_short_split: 5, max_value: 8_f32, reversed: true }),
+            YScaleSpec::Log(YLogScale { base: 2_f32, from: 0, to: 5, reversed: false }),
+            YScaleSpec::Symlog(YSymlogScale { base: 10_f32, threshold: 1_f32, min_value: -10_f32, max_value: 1000_f32, reversed: false }),
+            YScaleSpec::Reciprocal(YReciprocalScale {
+                min_value: 1_f32, max_value: 4_f32, factor: 1_f32,
+                tick_domain: TickDomain::Original, v_great_split: 3, v_short_split: 2, reversed: true
+            }),
+            YScaleSpec::Power(YPowerScale {
+                exponent: 2_f32, min_value: -5_f32, max_value: 5_f32, v_great_split: 5, v_short_split: 2, reversed: false
+            }),
+            YScaleSpec::Broken(YBrokenScale {
+                segments: vec![(0_f32, 1_f32), (2_f32, 3_f32), (9_f32, 10_f32)], gap: 0.02,
+                v_great_split: 2, v_short_split: 2, reversed: false
+            }),
+            YScaleSpec::Time(YTimeScale {
+                start: 0_f64, end: 7200.5_f64, v_great_split: 4, v_short_split: 3, reversed: true
+            }),
+        ]
+    }
+
+    fn series() -> Vec<SeriesSpec> {
+        vec![
+            SeriesSpec::Bar(BarChart {
+                series: vec![
+                    BarSeries { name: "first".to_string(), values: vec![1.5, 2_f32, 0.25], fill: "red".to_string() },
+                    BarSeries { name: "second".to_string(), values: vec![3_f32], fill: "#00ff00".to_string() },
+                ],
+                layout: BarLayout::Stacked,
+                width: 0.8
+            }),
+            SeriesSpec::Histogram(Histogram {
+                name: "histogram".to_string(),
+                values: vec![1_f32, 2.5, 2.75, 4_f32, 9_f32],
+                binning: Binning::Width(0.5),
+                normalisation: Normalisation::Density,
+                fill: "gray".to_string(),
+                normal_pdf: true
+            }),
+            SeriesSpec::BoxPlot(BoxPlot {
+                name: "boxes".to_string(),
+                groups: vec![vec![1_f32, 2_f32, 3_f32, 40_f32], vec![], vec![0.5]],
+                width: 0.6,
+                fill: "white".to_string(),
+                violin: true
+            }),
+            SeriesSpec::Fill(FillBetween {
+                // どの軸でも写せる値にする
+                curve: vec![Vec2::vec2(1_f32, 1_f32), Vec2::vec2(2_f32, 2_f32)],
+                base: FillBase::Curve(vec![Vec2::vec2(1_f32, 0.5), Vec2::vec2(2_f32, 0.25)]),
+                fill: "blue".to_string(),
+                opacity: 0.3,
+                hatch: Some(Hatch { angle: 45_f32, spacing: 20_f32, colour: "black".to_string() })
+            }),
+            SeriesSpec::Fill(FillBetween {
+                curve: vec![Vec2::vec2(1_f32, 1_f32), Vec2::vec2(2_f32, 2_f32)],
+                base: FillBase::Baseline(0.5),
+                fill: "blue".to_string(),
+                opacity: 1_f32,
+                hatch: None
+            }),
+        ]
+    }
+
+    fn graph(x_scale:&XScaleSpec, y_scale:&YScaleSpec) -> Graph {
+        Graph {
+            graph_paper: graph_paper(),
+            x_scale: x_scale.to_scale(),
+            y_scale: y_scale.to_scale(),
+            series: series().iter().map(SeriesSpec::to_series).collect()
+        }
+    }
+
+    #[test]
+    fn round_trip_every_variant() {
+        let (xs, ys) = (x_scales(), y_scales());
+        // 全ての軸が一度は現れるよう、多い方に合わせて組み合わせる
+        for i in 0..xs.len().max(ys.len()) {
+            let (x_scale, y_scale) = (&xs[i % xs.len()], &ys[i % ys.len()]);
+            let original = graph(x_scale, y_scale);
+            let text = original.to_spec().unwrap().to_toml().unwrap();
+            let restored = GraphSpec::from_toml(&text).unwrap().to_graph(Path::new("")).unwrap();
+            assert_eq!(original.serialise().unwrap(), restored.serialise().unwrap(), "{}", text);
+            let text = original.to_spec().unwrap().to_json().unwrap();
+            let restored = GraphSpec::from_json(&text).unwrap().to_graph(Path::new("")).unwrap();
+            assert_eq!(original.serialise().unwrap(), restored.serialise().unwrap(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn csv_source_is_relative_to_spec() {
+        let base = std::env::temp_dir().join(format!("monet-spec-{}", std::process::id()));
+        fs::create_dir_all(base.join("data")).unwrap();
+        fs::create_dir_all(base.join("specs")).unwrap();
+        let csv = base.join("data").join("points.csv");
+        fs::write(&csv, "x,y\n1,2\n3,oops\n5,6\n").unwrap();
+        let source = CsvSource::new(&csv, &base, vec![(0, 1)]);
+        assert_eq!(source.path, Path::new("data").join("points.csv"));
+        // 設定ファイルの外にあるCSVはそのまま持つ
+        let outside = CsvSource::new(&csv, &base.join("specs"), vec![(0, 1)]);
+        assert_eq!(outside.path, csv.canonicalize().unwrap());
+        let x_scale = XScaleSpec::Linear(XLinearScale { h_great_split: 5, h_short_split: 2, max_value: 10_f32, reversed: false });
+        let y_scale = YScaleSpec::Linear(YLinearScale { v_great_split: 5, v_short_split: 2, max_value: 10_f32, reversed: false });
+        let points = source.read_points(&base, &x_scale, &y_scale);
+        fs::remove_dir_all(&base).unwrap();
+        assert_eq!(points.unwrap(), [Vec2::vec2(1_f32, 2_f32), Vec2::vec2(5_f32, 6_f32)]);
+    }
+    #[test]
+    fn csv_columns_keep_headers() {
+        let path = std::env::temp_dir().join(format!("monet-columns-{}.csv", std::process::id()));
+        fs::write(&path, "time,weight\n2024-05-01,3\n2024-05-02,\n").unwrap();
+        let read = CsvColumns::read(&path);
+        fs::remove_file(&path).unwrap();
+        let read = read.unwrap();
+        assert_eq!(read.headers, ["time", "weight"]);
+        assert_eq!(read.columns, [vec!["2024-05-01", "2024-05-02"], vec!["3", ""]]);
+        assert!(CsvColumns::read(Path::new("/nonexistent/monet.csv")).is_err());
+    }
+
+    #[test]
+    fn time_span_is_never_empty() {
+        assert_eq!(time_span([3_f64, 1_f64, 2_f64].into_iter()), (1_f64, 3_f64));
+        assert_eq!(time_span([5_f64, 5_f64].into_iter()), (5_f64, 6_f64));
+        assert_eq!(time_span(std::iter::empty()), (0_f64, 1_f64));
+    }
+}
@@ -0,0 +1,275 @@
+use std::fmt;
+use std::io::{self, BufWriter, Write};
+
+use crate::math::Vec2;
+use crate::render::{
+    Backend,
+    Element,
+    HorizontalAnchor,
+    PathCommand,
+    Style,
+    Text,
+    TextSetting,
+    VerticalAnchor
+};
+
+/// 深さの分だけタブで字下げする
+#[derive(Clone, Copy)]
+pub(crate) struct Indent(pub usize);
+impl fmt::Display for Indent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for _ in 0..self.0 {
+            f.write_str("\t")?;
+        }
+        Ok(())
+    }
+}
+
+/// 属性値と文字列を XML として正しい形にする
+pub(crate) struct Escaped<'a>(pub &'a str);
+impl fmt::Display for Escaped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // 置き換えの要らない区間はまとめて書く
+        let mut last = 0;
+        for (i, c) in self.0.char_indices() {
+            let escaped = match c {
+                '&' => "&amp;",
+                '<' => "&lt;",
+                '>' => "&gt;",
+                '"' => "&quot;",
+                '\'' => "&apos;",
+                _ => continue
+            };
+            f.write_str(&self.0[last..i])?;
+            f.write_str(escaped)?;
+            last = i + c.len_utf8();
+        }
+        f.write_str(&self.0[last..])
+    }
+}
+
+/// 文字の設定を属性にする
+struct TextAttributes<'a>(&'a TextSetting);
+impl fmt::Display for TextAttributes<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, " font-size=\"{}pt\"", self.0.font_size)?;
+        if let Some(s) = &self.0.v_anchor {
+            write!(f, " dominant-baseline=\"{}\"", match s {
+                VerticalAnchor::Top => "hanging",
+                VerticalAnchor::Centre => "middle",
+                VerticalAnchor::Bottom => "auto"
+            })?;
+        }
+        if let Some(s) = &self.0.h_anchor {
+            write!(f, " text-anchor=\"{}\"", match s {
+                HorizontalAnchor::Start => "start",
+                HorizontalAnchor::Centre => "middle",
+                HorizontalAnchor::End => "end"
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// 線と塗りの指定を属性にする
+struct StyleAttributes<'a>(&'a Style);
+impl fmt::Display for StyleAttributes<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(s) = &self.0.fill {
+            write!(f, " fill=\"{}\"", Escaped(s))?;
+        }
+        if let Some(s) = self.0.fill_opacity {
+            write!(f, " fill-opacity=\"{}\"", s)?;
+        }
+        if let Some(s) = &self.0.stroke {
+            write!(f, " stroke=\"{}\"", Escaped(s))?;
+        }
+        if let Some(s) = self.0.stroke_width {
+            write!(f, " stroke-width=\"{}\"", s)?;
+        }
+        Ok(())
+    }
+}
+
+/// 描画命令を`d`属性にする
+struct PathData<'a>(&'a [PathCommand]);
+impl fmt::Display for PathData<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, command) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            match command {
+                PathCommand::MoveTo(p) => write!(f, "M{},{}", p.x, p.y)?,
+                PathCommand::LineTo(p) => write!(f, "L{},{}", p.x, p.y)?,
+                PathCommand::Close => f.write_str("Z")?
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 点の並びを`points`属性にする
+struct Points<'a>(&'a [Vec2]);
+impl fmt::Display for Points<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, p) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{},{}", p.x, p.y)?;
+        }
+        Ok(())
+    }
+}
+
+/// 開いている`<g>`
+struct OpenGroup {
+    id: Option<String>,
+    // 切り抜きのために内側へ開いた`<g>`がある
+    clipped: bool,
+}
+
+/// SVGを要素ごとに書き出す描画先。書き出しは`BufWriter`でまとめて行う
+pub struct SvgBackend<W:Write> {
+    out: BufWriter<W>,
+    groups: Vec<OpenGroup>,
+    // 開いている`<defs>`の数
+    defs_depth: usize,
+    // 名前のないまとまりの切り抜きに振る番号
+    clip_count: usize,
+}
+impl<W:Write> SvgBackend<W> {
+    /// `<svg>`を開く
+    pub fn new(out:W, size:Vec2) -> io::Result<Self> {
+        let mut out = BufWriter::new(out);
+        writeln!(
+            out,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">",
+            size.x, size.y, size.x, size.y
+        )?;
+        Ok(SvgBackend { out, groups: Vec::new(), defs_depth: 0, clip_count: 0 })
+    }
+    /// 開いているまとまりと`<svg>`を閉じて、書き出し先を返す
+    pub fn finish(mut self) -> io::Result<W> {
+        while !self.groups.is_empty() {
+            self.end_group()?;
+        }
+        write!(self.out, "</svg>")?;
+        self.out.into_inner().map_err(|e| e.into_error())
+    }
+    fn indent(&self) -> Indent {
+        Indent(1 + self.defs_depth + self.groups.iter().map(|g| if g.clipped { 2 } else { 1 }).sum::<usize>())
+    }
+}
+
+impl<W:Write> Backend for SvgBackend<W> {
+    fn begin_group(&mut self, id:Option<&str>, class:Option<&str>) -> io::Result<()> {
+        write!(self.out, "{}<g", self.indent())?;
+        if let Some(s) = id {
+            write!(self.out, " id=\"{}\"", Escaped(s))?;
+        }
+        if let Some(s) = class {
+            write!(self.out, " class=\"{}\"", Escaped(s))?;
+        }
+        writeln!(self.out, ">")?;
+        self.groups.push(OpenGroup { id: id.map(|s| s.to_string()), clipped: false });
+        Ok(())
+    }
+    fn end_group(&mut self) -> io::Result<()> {
+        if let Some(group) = self.groups.pop() {
+            let indent = self.indent();
+            if group.clipped {
+                writeln!(self.out, "{}\t</g>", indent)?;
+            }
+            writeln!(self.out, "{}</g>", indent)?;
+        }
+        Ok(())
+    }
+    fn clip(&mut self, commands:&[PathCommand]) -> io::Result<()> {
+        // 名前のあるまとまりは名前から、ないものは番号から切り抜きのidを作る
+        let id = match self.groups.last().and_then(|g| g.id.as_ref()) {
+            Some(id) => format!("{}-clip", id),
+            None => {
+                self.clip_count += 1;
+                format!("clip-{}", self.clip_count)
+            }
+        };
+        self.draw(&Element::Defs(vec![Element::ClipPath { id: id.clone(), commands: commands.to_vec() }]))?;
+        writeln!(self.out, "{}<g clip-path=\"url(#{})\">", self.indent(), Escaped(&id))?;
+        if let Some(group) = self.groups.last_mut() {
+            group.clipped = true;
+        }
+        Ok(())
+    }
+    fn defs(&mut self, elements:&[Element]) -> io::Result<()> {
+        writeln!(self.out, "{}<defs>", self.indent())?;
+        self.defs_depth += 1;
+        self.draw_all(elements)?;
+        self.defs_depth -= 1;
+        writeln!(self.out, "{}</defs>", self.indent())
+    }
+    fn clip_path(&mut self, id:&str, commands:&[PathCommand]) -> io::Result<()> {
+        let indent = self.indent();
+        writeln!(self.out, "{}<clipPath id=\"{}\">", indent, Escaped(id))?;
+        writeln!(self.out, "{}\t<path d=\"{}\" />", indent, PathData(commands))?;
+        writeln!(self.out, "{}</clipPath>", indent)
+    }
+    fn line(&mut self, from:Vec2, to:Vec2, style:&Style) -> io::Result<()> {
+        writeln!(
+            self.out,
+            "{}<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"{} />",
+            self.indent(), from.x, from.y, to.x, to.y, StyleAttributes(style)
+        )
+    }
+    fn polyline(&mut self, points:&[Vec2], closed:bool, style:&Style) -> io::Result<()> {
+        writeln!(
+            self.out,
+            "{}<{} points=\"{}\"{} />",
+            self.indent(), if closed { "polygon" } else { "polyline" }, Points(points), StyleAttributes(style)
+        )
+    }
+    fn path(&mut self, commands:&[PathCommand], style:&Style) -> io::Result<()> {
+        writeln!(
+            self.out,
+            "{}<path d=\"{}\"{} />",
+            self.indent(), PathData(commands), StyleAttributes(style)
+        )
+    }
+    fn rect(&mut self, origin:Vec2, size:Vec2, style:&Style) -> io::Result<()> {
+        writeln!(
+            self.out,
+            "{}<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"{} />",
+            self.indent(), origin.x, origin.y, size.x, size.y, StyleAttributes(style)
+        )
+    }
+    fn circle(&mut self, centre:Vec2, radius:f32, style:&Style) -> io::Result<()> {
+        writeln!(
+            self.out,
+            "{}<circle cx=\"{}\" cy=\"{}\" r=\"{}\"{} />",
+            self.indent(), centre.x, centre.y, radius, StyleAttributes(style)
+        )
+    }
+    fn text(&mut self, text:&Text) -> io::Result<()> {
+        write!(
+            self.out,
+            "{}<text x=\"{}\" y=\"{}\"{}>",
+            self.indent(), text.anchor.x, text.anchor.y, TextAttributes(&text.setting)
+        )?;
+        for span in &text.spans {
+            if span.dx.is_none() && span.dy.is_none() {
+                write!(self.out, "{}", Escaped(&span.text))?;
+                continue;
+            }
+            write!(self.out, "<tspan")?;
+            if let Some(dx) = span.dx {
+                write!(self.out, " dx=\"{}\"", dx)?;
+            }
+            if let Some(dy) = span.dy {
+                write!(self.out, " dy=\"{}\"", dy)?;
+            }
+            write!(self.out, ">{}</tspan>", Escaped(&span.text))?;
+        }
+        writeln!(self.out, "</text>")
+    }
+}
@@ -0,0 +1,364 @@
+use std::fmt::Write as _;
+use std::io;
+
+use crate::error::Error;
+use crate::graph_paper::Graph;
+use crate::math::Vec2;
+use crate::render::{Backend, HorizontalAnchor, PathCommand, Rgb, Style, Text, VerticalAnchor};
+
+// 点字の1文字の点の並び (横2 x 縦4) と、各点のビット
+const BRAILLE_BITS:[[u32; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+// ブロック要素の1文字の点の並び (横2 x 縦2)。添字は左上, 右上, 左下, 右下の順のビット
+const BLOCKS:[char; 16] = [
+    ' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█'
+];
+// 塗りをこれより薄くすると輪郭だけを描く
+const FILL_OPACITY_THRESHOLD:f32 = 0.5;
+
+/// 1文字に詰める点の並べ方
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Glyphs {
+    // 点字 (1文字に横2 x 縦4点)
+    Braille,
+    // ブロック要素 (1文字に横2 x 縦2点)
+    Block,
+}
+impl Glyphs {
+    /// 1文字あたりの点の数 (横, 縦)
+    fn dots(&self) -> (usize, usize) {
+        match self {
+            Glyphs::Braille => (2, 4),
+            Glyphs::Block => (2, 2)
+        }
+    }
+}
+
+/// 文字で描くときの設定
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TerminalOptions {
+    // 文字数 (横, 縦)
+    pub columns: usize,
+    pub rows: usize,
+    pub glyphs: Glyphs,
+    // ANSIエスケープで色を付ける
+    pub colour: bool,
+}
+
+/// 文字の格子へ点を打つ描画先。`finish`で行を繋げた文字列を返す
+pub struct TerminalBackend {
+    options: TerminalOptions,
+    // 用紙の1単位あたりの点の数 (横, 縦)
+    scale: Vec2,
+    // 点の格子の大きさ
+    width: usize,
+    height: usize,
+    // 点ごとの色。`None`は点がない
+    dots: Vec<Option<Rgb>>,
+    // 点より優先して書く文字。幅の広い文字の右半分は`'\0'`で埋める
+    letters: Vec<Option<(char, Rgb)>>,
+    // 開いているまとまりごとの切り抜き (用紙の座標の多角形)
+    clips: Vec<Option<Vec<Vec2>>>,
+}
+
+impl TerminalBackend {
+    pub fn new(size:Vec2, options:TerminalOptions) -> Result<Self, Error> {
+        for (name, value) in [("columns", options.columns), ("rows", options.rows)] {
+            if value == 0 {
+                return Err(Error::InvalidParameter { name, value: 0_f32 });
+            }
+        }
+        let (dx, dy) = options.glyphs.dots();
+        let (width, height) = (options.columns * dx, options.rows * dy);
+        Ok(TerminalBackend {
+            options,
+            scale: Vec2::vec2(width as f32 / size.x, height as f32 / size.y),
+            width,
+            height,
+            dots: vec![None; width * height],
+            letters: vec![None; options.columns * options.rows],
+            clips: Vec::new()
+        })
+    }
+
+    /// 行ごとに改行した文字列にする
+    pub fn finish(self) -> String {
+        let (dx, dy) = self.options.glyphs.dots();
+        let bit = |i:usize, j:usize| match self.options.glyphs {
+            Glyphs::Braille => BRAILLE_BITS[i][j],
+            Glyphs::Block => 1 << (j * 2 + i)
+        };
+        let mut res = String::new();
+        for row in 0..self.options.rows {
+            // 直前の文字の色。同じ色が続く間はエスケープを書かない
+            let mut current:Option<Rgb> = None;
+            for column in 0..self.options.columns {
+                let (c, colour) = match self.letters[row * self.options.columns + column] {
+                    Some(letter) => (letter.0, Some(letter.1)),
+                    None => {
+                        let mut bits = 0;
+                        let mut colour = None;
+                        for i in 0..dx {
+                            for j in 0..dy {
+                                if let Some(c) = self.dots[(row * dy + j) * self.width + column * dx + i] {
+                                    bits |= bit(i, j);
+                                    colour = Some(c);
+                                }
+                            }
+                        }
+                        let c = match self.options.glyphs {
+                            Glyphs::Braille if bits == 0 => ' ',
+                            Glyphs::Braille => char::from_u32(0x2800 + bits).expect("braille patterns are valid chars"),
+                            Glyphs::Block => BLOCKS[bits as usize]
+                        };
+                        (c, colour)
+                    }
+                };
+                // 黒は端末の既定の色で書く
+                let colour = colour.filter(|c| *c != Rgb::BLACK);
+                if self.options.colour && colour != current {
+                    match colour {
+                        Some(Rgb { r, g, b }) => write!(res, "\x1b[38;2;{};{};{}m", r, g, b),
+                        None => write!(res, "\x1b[0m")
+                    }.expect("writing to a String doesn't fail");
+                    current = colour;
+                }
+                if c != '\0' {
+                    res.push(c);
+                }
+            }
+            if self.options.colour && current.is_some() {
+                res.push_str("\x1b[0m");
+            }
+            res.push('\n');
+        }
+        res
+    }
+
+    /// 点の中心の用紙での位置
+    fn centre(&self, i:usize, j:usize) -> Vec2 {
+        Vec2::vec2((i as f32 + 0.5_f32) / self.scale.x, (j as f32 + 0.5_f32) / self.scale.y)
+    }
+    fn visible(&self, p:Vec2) -> bool {
+        self.clips.iter().flatten().all(|polygon| contains(polygon, p))
+    }
+    /// 点を打つ。白は背景として点を消す
+    fn set(&mut self, i:usize, j:usize, colour:Rgb) {
+        let white = Rgb { r: 255, g: 255, b: 255 };
+        self.dots[j * self.width + i] = if colour == white { None } else { Some(colour) };
+    }
+    /// 用紙の位置にある点を打つ
+    fn plot(&mut self, p:Vec2, colour:Rgb) {
+        let (i, j) = (p.x * self.scale.x, p.y * self.scale.y);
+        if !(i >= 0_f32 && j >= 0_f32 && (i as usize) < self.width && (j as usize) < self.height) {
+            return;
+        }
+        let (i, j) = (i as usize, j as usize);
+        if self.visible(self.centre(i, j)) {
+            self.set(i, j, colour);
+        }
+    }
+    /// 点を結ぶ線分
+    fn segment(&mut self, from:Vec2, to:Vec2, colour:Rgb) {
+        let steps = ((to.x - from.x) * self.scale.x).abs()
+            .max(((to.y - from.y) * self.scale.y).abs())
+            .ceil()
+            .max(1_f32);
+        if !steps.is_finite() {
+            return;
+        }
+        for k in 0..(steps as usize + 1) {
+            let t = k as f32 / steps;
+            self.plot(from + (to - from).mul(t), colour);
+        }
+    }
+    /// 多角形の内側の点を打つ
+    fn fill_polygon(&mut self, polygon:&[Vec2], colour:Rgb) {
+        let Some((lo, hi)) = bounds(polygon) else {
+            return;
+        };
+        let (i0, j0) = ((lo.x * self.scale.x).max(0_f32) as usize, (lo.y * self.scale.y).max(0_f32) as usize);
+        let i1 = ((hi.x * self.scale.x).ceil().max(0_f32) as usize).min(self.width);
+        let j1 = ((hi.y * self.scale.y).ceil().max(0_f32) as usize).min(self.height);
+        for j in j0..j1 {
+            for i in i0..i1 {
+                let p = self.centre(i, j);
+                if contains(polygon, p) && self.visible(p) {
+                    self.set(i, j, colour);
+                }
+            }
+        }
+    }
+    /// 輪郭を塗り、線を引く
+    fn paint(&mut self, polygons:&[Vec<Vec2>], closed:bool, style:&Style, fillable:bool) {
+        let opacity = style.fill_opacity.unwrap_or(1_f32);
+        if let Some(colour) = style.fill_colour().filter(|_| fillable && opacity >= FILL_OPACITY_THRESHOLD) {
+            for polygon in polygons {
+                self.fill_polygon(polygon, colour);
+            }
+        }
+        // 薄い塗りは輪郭で表す
+        let outline = match style.stroke_colour() {
+            Some(colour) => Some(colour),
+            None => style.fill_colour().filter(|_| fillable && opacity < FILL_OPACITY_THRESHOLD)
+        };
+        if let Some(colour) = outline {
+            for polygon in polygons {
+                for pair in polygon.windows(2) {
+                    self.segment(pair[0], pair[1], colour);
+                }
+                if let (true, Some(first), Some(last)) = (closed, polygon.first(), polygon.last()) {
+                    self.segment(*last, *first, colour);
+                }
+            }
+        }
+    }
+}
+
+/// 端末で2文字分の幅を取る文字 (CJKと全角形)
+fn is_wide(c:char) -> bool {
+    matches!(c as u32,
+        0x1100..=0x115F | 0x2E80..=0x303E | 0x3041..=0x33FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF | 0xAC00..=0xD7A3 | 0xF900..=0xFAFF | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60 | 0xFFE0..=0xFFE6 | 0x20000..=0x3FFFD
+    )
+}
+
+/// 描画命令を、部分ごとの点の並びにする
+fn flatten(commands:&[PathCommand]) -> Vec<Vec<Vec2>> {
+    let mut res:Vec<Vec<Vec2>> = Vec::new();
+    for command in commands {
+        match command {
+            PathCommand::MoveTo(p) => res.push(vec![*p]),
+            PathCommand::LineTo(p) => match res.last_mut() {
+                Some(polygon) => polygon.push(*p),
+                None => res.push(vec![*p])
+            },
+            PathCommand::Close => {}
+        }
+    }
+    res
+}
+
+/// 点の並びを囲む長方形 (左上, 右下)
+fn bounds(polygon:&[Vec2]) -> Option<(Vec2, Vec2)> {
+    let first = polygon.first()?;
+    Some(polygon.iter().fold((*first, *first), |(lo, hi), p| (
+        Vec2::vec2(lo.x.min(p.x), lo.y.min(p.y)),
+        Vec2::vec2(hi.x.max(p.x), hi.y.max(p.y))
+    )))
+}
+
+/// 点が多角形の内側にあるか (偶奇規則)
+fn contains(polygon:&[Vec2], p:Vec2) -> bool {
+    let mut inside = false;
+    for (i, a) in polygon.iter().enumerate() {
+        let b = polygon[(i + polygon.len() - 1) % polygon.len()];
+        if (a.y > p.y) != (b.y > p.y) && p.x < (b.x - a.x) * (p.y - a.y) / (b.y - a.y) + a.x {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+impl Backend for TerminalBackend {
+    fn begin_group(&mut self, _id:Option<&str>, _class:Option<&str>) -> io::Result<()> {
+        self.clips.push(None);
+        Ok(())
+    }
+    fn end_group(&mut self) -> io::Result<()> {
+        self.clips.pop();
+        Ok(())
+    }
+    fn clip(&mut self, commands:&[PathCommand]) -> io::Result<()> {
+        // 切り抜きは最初の部分だけを使う
+        let polygon = flatten(commands).into_iter().next().unwrap_or_default();
+        if let Some(top) = self.clips.last_mut() {
+            *top = Some(polygon);
+        }
+        Ok(())
+    }
+    fn line(&mut self, from:Vec2, to:Vec2, style:&Style) -> io::Result<()> {
+        self.paint(&[vec![from, to]], false, style, false);
+        Ok(())
+    }
+    fn polyline(&mut self, points:&[Vec2], closed:bool, style:&Style) -> io::Result<()> {
+        self.paint(&[points.to_vec()], closed, style, true);
+        Ok(())
+    }
+    fn path(&mut self, commands:&[PathCommand], style:&Style) -> io::Result<()> {
+        let closed = commands.contains(&PathCommand::Close);
+        self.paint(&flatten(commands), closed, style, true);
+        Ok(())
+    }
+    fn rect(&mut self, origin:Vec2, size:Vec2, style:&Style) -> io::Result<()> {
+        let corners = [
+            origin,
+            origin + Vec2::vec2(size.x, 0_f32),
+            origin + size,
+            origin + Vec2::vec2(0_f32, size.y)
+        ];
+        self.paint(&[corners.to_vec()], true, style, true);
+        Ok(())
+    }
+    fn circle(&mut self, centre:Vec2, radius:f32, style:&Style) -> io::Result<()> {
+        // 1点より小さな円は中心の点で表す
+        if radius * self.scale.x.max(self.scale.y) < 1_f32 {
+            if let Some(colour) = style.fill_colour().or(style.stroke_colour()) {
+                self.plot(centre, colour);
+            }
+            return Ok(());
+        }
+        let steps = (radius * self.scale.x.max(self.scale.y) * 8_f32).ceil() as usize;
+        let polygon = (0..steps)
+            .map(|k| {
+                let angle = k as f32 / steps as f32 * std::f32::consts::TAU;
+                centre + Vec2::vec2(angle.cos(), angle.sin()).mul(radius)
+            })
+            .collect::<Vec<Vec2>>();
+        self.paint(&[polygon], true, style, true);
+        Ok(())
+    }
+    fn text(&mut self, text:&Text) -> io::Result<()> {
+        let content = text.content();
+        let length = content.chars().map(|c| if is_wide(c) { 2 } else { 1 }).sum::<usize>();
+        let (columns, rows) = (self.options.columns, self.options.rows);
+        if length == 0 || length > columns {
+            return Ok(());
+        }
+        let (dx, dy) = self.options.glyphs.dots();
+        let x = text.anchor.x * self.scale.x / dx as f32;
+        let y = text.anchor.y * self.scale.y / dy as f32;
+        let column = match text.setting.h_anchor {
+            None | Some(HorizontalAnchor::Start) => x.floor(),
+            Some(HorizontalAnchor::Centre) => (x - length as f32 / 2_f32).round(),
+            Some(HorizontalAnchor::End) => x.ceil() - length as f32
+        };
+        let row = match text.setting.v_anchor {
+            None | Some(VerticalAnchor::Bottom) => y.ceil() - 1_f32,
+            Some(VerticalAnchor::Centre) => y.floor(),
+            Some(VerticalAnchor::Top) => y.ceil()
+        };
+        // はみ出す文字列は内側へ寄せる
+        let column = (column.max(0_f32) as usize).min(columns - length);
+        let row = (row.max(0_f32) as usize).min(rows - 1);
+        let mut k = row * columns + column;
+        for c in content.chars() {
+            self.letters[k] = Some((c, Rgb::BLACK));
+            k += 1;
+            if is_wide(c) {
+                self.letters[k] = Some(('\0', Rgb::BLACK));
+                k += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Graph {
+    /// 端末に表示する文字列にする。軸の写し方は他の出力形式と同じ
+    pub fn to_terminal(&self, options:TerminalOptions) -> Result<String, Error> {
+        let mut backend = TerminalBackend::new(self.graph_paper.size, options)?;
+        self.render(&mut backend)?;
+        Ok(backend.finish())
+    }
+}
@@ -0,0 +1,450 @@
+use std::fmt;
+use std::io::{self, BufWriter, Write};
+
+use crate::error::Error;
+use crate::graph_paper::{AxisLayout, Graph, P_RADIUS};
+use crate::math::Vec2;
+use crate::render::{Backend, HorizontalAnchor, PathCommand, Rgb, Style, Text, VerticalAnchor};
+use crate::svg::Indent;
+
+/// 数値を小数点以下4桁までで書く
+struct Number(f32);
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = format!("{:.4}", self.0);
+        let s = s.trim_end_matches('0').trim_end_matches('.');
+        f.write_str(if s == "-0" { "0" } else { s })
+    }
+}
+
+/// 軸の値を有効数字6桁までで書く。対数軸の小さな値も桁を落とさない
+struct Value(f32);
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 == 0_f32 {
+            return f.write_str("0");
+        }
+        let decimals = (5_f32 - self.0.abs().log10().floor()).max(0_f32) as usize;
+        let s = format!("{:.*}", decimals, self.0);
+        f.write_str(if s.contains('.') { s.trim_end_matches('0').trim_end_matches('.') } else { &s })
+    }
+}
+
+/// 用紙の単位 (0.1mm) の長さをmmで書く
+struct Mm(f32);
+impl fmt::Display for Mm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}mm", Number(self.0 / 10_f32))
+    }
+}
+
+/// 文字列をLaTeXの特殊文字が効かない形にする
+struct Escaped<'a>(&'a str);
+impl fmt::Display for Escaped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '\\' => f.write_str("\\textbackslash{}")?,
+                '~' => f.write_str("\\textasciitilde{}")?,
+                '^' => f.write_str("\\textasciicircum{}")?,
+                '<' => f.write_str("\\textless{}")?,
+                '>' => f.write_str("\\textgreater{}")?,
+                '&' | '%' | '$' | '#' | '_' | '{' | '}' => write!(f, "\\{}", c)?,
+                _ => write!(f, "{}", c)?
+            }
+        }
+        Ok(())
+    }
+}
+
+/// xcolorの色指定
+struct Colour(Rgb);
+impl fmt::Display for Colour {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{rgb,255:red,{};green,{};blue,{}}}", self.0.r, self.0.g, self.0.b)
+    }
+}
+
+/// 線と塗りの指定をTikZのオプションにする
+struct StyleOptions<'a> {
+    style: &'a Style,
+    fillable: bool,
+}
+impl StyleOptions<'_> {
+    fn is_empty(&self) -> bool {
+        self.style.stroke_colour().is_none() && !(self.fillable && self.style.fill_colour().is_some())
+    }
+}
+impl fmt::Display for StyleOptions<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut options = Vec::new();
+        if let Some(colour) = self.style.stroke_colour() {
+            options.push(format!("draw={}", Colour(colour)));
+            options.push(format!("line width={}", Mm(self.style.width())));
+        }
+        if let Some(colour) = self.style.fill_colour().filter(|_| self.fillable) {
+            options.push(format!("fill={}", Colour(colour)));
+            if let Some(opacity) = self.style.fill_opacity {
+                options.push(format!("fill opacity={}", Number(opacity)));
+            }
+        }
+        f.write_str(&options.join(", "))
+    }
+}
+
+/// pgfplotsの`\addplot`の線と塗りの指定。ない方は`none`と明示する
+struct PlotOptions<'a> {
+    style: &'a Style,
+    fillable: bool,
+}
+impl fmt::Display for PlotOptions<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.style.stroke_colour() {
+            Some(colour) => write!(f, "color={}, line width={}", Colour(colour), Mm(self.style.width()))?,
+            None => f.write_str("draw=none")?
+        }
+        match self.style.fill_colour().filter(|_| self.fillable) {
+            Some(colour) => {
+                write!(f, ", fill={}", Colour(colour))?;
+                if let Some(opacity) = self.style.fill_opacity {
+                    write!(f, ", fill opacity={}", Number(opacity))?;
+                }
+            },
+            None => f.write_str(", fill=none")?
+        }
+        Ok(())
+    }
+}
+
+/// pgfplotsの軸の中に書くときのプロット領域 (左上, 大きさ) と両軸
+struct AxisFrame {
+    origin: Vec2,
+    size: Vec2,
+    x_axis: AxisLayout,
+    y_axis: AxisLayout,
+}
+impl AxisFrame {
+    /// 用紙の位置を軸の値に戻す
+    fn value(&self, p:Vec2) -> Vec2 {
+        Vec2::vec2(
+            axis_value(&self.x_axis, (p.x - self.origin.x) / self.size.x),
+            axis_value(&self.y_axis, 1_f32 - (p.y - self.origin.y) / self.size.y)
+        )
+    }
+}
+
+/// 軸の長さに対する割合 (左端 / 下端から) を軸の値にする
+fn axis_value(axis:&AxisLayout, ratio:f32) -> f32 {
+    let ratio = if axis.reversed { 1_f32 - ratio } else { ratio };
+    let (from, to) = axis.range;
+    match axis.log_base {
+        Some(_) => (from.ln() + ratio * (to.ln() - from.ln())).exp(),
+        None => from + ratio * (to - from)
+    }
+}
+
+/// TikZのノードの基準点
+fn node_anchor(text:&Text) -> &'static str {
+    match (text.setting.v_anchor, text.setting.h_anchor) {
+        (Some(VerticalAnchor::Top), None | Some(HorizontalAnchor::Start)) => "north west",
+        (Some(VerticalAnchor::Top), Some(HorizontalAnchor::Centre)) => "north",
+        (Some(VerticalAnchor::Top), Some(HorizontalAnchor::End)) => "north east",
+        (Some(VerticalAnchor::Centre), None | Some(HorizontalAnchor::Start)) => "west",
+        (Some(VerticalAnchor::Centre), Some(HorizontalAnchor::Centre)) => "center",
+        (Some(VerticalAnchor::Centre), Some(HorizontalAnchor::End)) => "east",
+        (None | Some(VerticalAnchor::Bottom), None | Some(HorizontalAnchor::Start)) => "base west",
+        (None | Some(VerticalAnchor::Bottom), Some(HorizontalAnchor::Centre)) => "base",
+        (None | Some(VerticalAnchor::Bottom), Some(HorizontalAnchor::End)) => "base east"
+    }
+}
+
+/// TikZの描画命令を書き出す描画先。
+/// 座標はmmで書き、`tikzpicture`の`y=-1mm`で用紙と同じく下向きを正とする
+pub struct TikzBackend<W:Write> {
+    out: BufWriter<W>,
+    // 字下げの深さ / 開いている`scope`の数
+    depth: usize,
+    scopes: usize,
+    // pgfplotsの軸の中に書くときの軸。線や面は`\addplot`で軸の値の座標として書く
+    frame: Option<AxisFrame>,
+}
+impl<W:Write> TikzBackend<W> {
+    /// `tikzpicture`を開く
+    pub fn new(out:W) -> io::Result<Self> {
+        let mut out = BufWriter::new(out);
+        writeln!(out, "\\begin{{tikzpicture}}[x=1mm, y=-1mm]")?;
+        Ok(TikzBackend { out, depth: 1, scopes: 0, frame: None })
+    }
+    /// 開いている`scope`と`tikzpicture`を閉じて、書き出し先を返す
+    pub fn finish(mut self) -> io::Result<W> {
+        while self.scopes > 0 {
+            self.end_group()?;
+        }
+        writeln!(self.out, "\\end{{tikzpicture}}")?;
+        self.out.into_inner().map_err(|e| e.into_error())
+    }
+    fn point(&self, p:Vec2) -> String {
+        match &self.frame {
+            Some(frame) => {
+                let value = frame.value(p);
+                format!("(axis cs:{},{})", Value(value.x), Value(value.y))
+            },
+            None => format!("({},{})", Number(p.x / 10_f32), Number(p.y / 10_f32))
+        }
+    }
+    fn path_data(&self, commands:&[PathCommand]) -> String {
+        commands.iter()
+            .enumerate()
+            .map(|(i, command)| match command {
+                PathCommand::MoveTo(p) => self.point(*p),
+                PathCommand::LineTo(p) if i == 0 => self.point(*p),
+                PathCommand::LineTo(p) => format!("-- {}", self.point(*p)),
+                PathCommand::Close => "-- cycle".to_string()
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+    /// 指定があれば`\path`に線と塗りを付けて書く
+    fn draw_path(&mut self, path:&str, style:&Style, fillable:bool) -> io::Result<()> {
+        let options = StyleOptions { style, fillable };
+        if options.is_empty() || path.is_empty() {
+            return Ok(());
+        }
+        writeln!(self.out, "{}\\path[{}] {};", Indent(self.depth), options, path)
+    }
+    /// 軸の中では線や面を部分路ごとに`\addplot`で書き、そうでなければ`\path`で書く
+    fn draw_commands(&mut self, commands:&[PathCommand], style:&Style, fillable:bool) -> io::Result<()> {
+        let Some(frame) = &self.frame else {
+            let path = self.path_data(commands);
+            return self.draw_path(&path, style, fillable);
+        };
+        if (StyleOptions { style, fillable }).is_empty() {
+            return Ok(());
+        }
+        let mut subpaths:Vec<Vec<Vec2>> = Vec::new();
+        for command in commands {
+            match command {
+                PathCommand::MoveTo(p) => subpaths.push(vec![frame.value(*p)]),
+                PathCommand::LineTo(p) => match subpaths.last_mut() {
+                    Some(subpath) => subpath.push(frame.value(*p)),
+                    None => subpaths.push(vec![frame.value(*p)])
+                },
+                // 始点へ戻って閉じる
+                PathCommand::Close => if let Some(subpath) = subpaths.last_mut() && let Some(first) = subpath.first() {
+                    subpath.push(*first);
+                }
+            }
+        }
+        for subpath in subpaths {
+            let coordinates = subpath.iter()
+                .filter(|p| p.x.is_finite() && p.y.is_finite())
+                .map(|p| format!("({},{})", Value(p.x), Value(p.y)))
+                .collect::<Vec<String>>();
+            if coordinates.len() < 2 {
+                continue;
+            }
+            writeln!(
+                self.out,
+                "{}\\addplot[forget plot, mark=none, {}] coordinates {{{}}};",
+                Indent(self.depth), PlotOptions { style, fillable }, coordinates.join(" ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl<W:Write> Backend for TikzBackend<W> {
+    fn begin_group(&mut self, _id:Option<&str>, _class:Option<&str>) -> io::Result<()> {
+        writeln!(self.out, "{}\\begin{{scope}}", Indent(self.depth))?;
+        self.depth += 1;
+        self.scopes += 1;
+        Ok(())
+    }
+    fn end_group(&mut self) -> io::Result<()> {
+        self.scopes = self.scopes.checked_sub(1)
+            .ok_or_else(|| io::Error::other("end_group was called without an open group"))?;
+        self.depth -= 1;
+        writeln!(self.out, "{}\\end{{scope}}", Indent(self.depth))
+    }
+    fn clip(&mut self, commands:&[PathCommand]) -> io::Result<()> {
+        writeln!(self.out, "{}\\clip {};", Indent(self.depth), self.path_data(commands))
+    }
+    fn line(&mut self, from:Vec2, to:Vec2, style:&Style) -> io::Result<()> {
+        if self.frame.is_some() {
+            return self.draw_commands(&PathCommand::through(&[from, to], false), style, false);
+        }
+        let path = format!("{} -- {}", self.point(from), self.point(to));
+        self.draw_path(&path, style, false)
+    }
+    fn polyline(&mut self, points:&[Vec2], closed:bool, style:&Style) -> io::Result<()> {
+        self.draw_commands(&PathCommand::through(points, closed), style, true)
+    }
+    fn path(&mut self, commands:&[PathCommand], style:&Style) -> io::Result<()> {
+        self.draw_commands(commands, style, true)
+    }
+    fn rect(&mut self, origin:Vec2, size:Vec2, style:&Style) -> io::Result<()> {
+        if self.frame.is_some() {
+            return self.draw_commands(&PathCommand::rect(origin, size), style, true);
+        }
+        let path = format!("{} rectangle {}", self.point(origin), self.point(origin + size));
+        self.draw_path(&path, style, true)
+    }
+    fn circle(&mut self, centre:Vec2, radius:f32, style:&Style) -> io::Result<()> {
+        if let Some(frame) = &self.frame {
+            // 軸の中では点の印として書く
+            let value = frame.value(centre);
+            if (StyleOptions { style, fillable: true }).is_empty() || !(value.x.is_finite() && value.y.is_finite()) {
+                return Ok(());
+            }
+            return writeln!(
+                self.out,
+                "{}\\addplot[forget plot, only marks, mark=*, mark size={}, mark options={{{}}}] coordinates {{({},{})}};",
+                Indent(self.depth), Mm(radius), PlotOptions { style, fillable: true }, Value(value.x), Value(value.y)
+            );
+        }
+        let path = format!("{} circle[radius={}]", self.point(centre), Mm(radius));
+        self.draw_path(&path, style, true)
+    }
+    fn text(&mut self, text:&Text) -> io::Result<()> {
+        let content = text.content();
+        if content.trim().is_empty() {
+            return Ok(());
+        }
+        // 文字は文書のフォントで組み、大きさだけを合わせる
+        let size = text.setting.font_size;
+        writeln!(
+            self.out,
+            "{}\\node[anchor={}, inner sep=0pt, font=\\fontsize{{{}pt}}{{{}pt}}\\selectfont] at {} {{{}}};",
+            Indent(self.depth), node_anchor(text), size, Number(size as f32 * 1.2_f32),
+            self.point(text.anchor), Escaped(&content)
+        )
+    }
+}
+
+/// 軸の設定をpgfplotsのオプションにする
+fn axis_options(axis:&AxisLayout, name:char) -> Vec<String> {
+    let ticks = |values:&[f32]| values.iter()
+        .map(|v| Number(*v).to_string())
+        .collect::<Vec<String>>()
+        .join(",");
+    let mut options = vec![
+        format!("{}min={}, {}max={}", name, Number(axis.range.0), name, Number(axis.range.1)),
+        format!("{}tick={{{}}}", name, ticks(&axis.great_ticks))
+    ];
+    if !axis.short_ticks.is_empty() {
+        options.push(format!("minor {}tick={{{}}}", name, ticks(&axis.short_ticks)));
+    }
+    if let Some(base) = axis.log_base {
+        options.push(format!("{}mode=log, log basis {}={}", name, name, Number(base)));
+    }
+    if axis.reversed {
+        options.push(format!("{} dir=reverse", name));
+    }
+    options
+}
+
+impl Graph {
+    /// 用紙をそのままTikZの図として書き出す。どの軸でも使える
+    pub fn write_tikz<W:Write>(&self, out:W) -> Result<W, Error> {
+        let mut backend = TikzBackend::new(out)?;
+        self.render(&mut backend)?;
+        Ok(backend.finish()?)
+    }
+    /// 軸をpgfplotsの`axis`として、プロット点を表として書き出す。
+    /// 系列は軸の値の座標の`\addplot`として軸の中に描く。リニア軸と対数軸だけを扱う
+    pub fn write_pgfplots<W:Write>(&self, out:W) -> Result<W, Error> {
+        self.validate()?;
+        let (Some(x_axis), Some(y_axis)) = (self.x_scale.layout(), self.y_scale.layout()) else {
+            return Err(Error::UnsupportedScale("a pgfplots axis"));
+        };
+        let graph_paper = &self.graph_paper;
+        let origin = Vec2::vec2(graph_paper.margin, graph_paper.margin);
+        let size = graph_paper.size - origin.mul(2_f32);
+        let mut options = vec![
+            format!("width={}, height={}, scale only axis", Mm(size.x), Mm(size.y)),
+            format!("axis line style={{line width={}}}", Mm(graph_paper.stroke_width)),
+            format!(
+                "major tick length={}, minor tick length={}",
+                Mm(graph_paper.great_split_length), Mm(graph_paper.short_split_length)
+            ),
+        ];
+        if !graph_paper.name.is_empty() {
+            options.push(format!("title={{{}}}", Escaped(&graph_paper.name)));
+        }
+        options.extend(axis_options(&x_axis, 'x'));
+        options.extend(axis_options(&y_axis, 'y'));
+        let frame = AxisFrame { origin, size, x_axis, y_axis };
+
+        let mut backend = TikzBackend::new(out)?;
+        writeln!(backend.out, "\t\\begin{{axis}}[")?;
+        writeln!(backend.out, "\t\t{}", options.join(",\n\t\t"))?;
+        writeln!(backend.out, "\t]")?;
+        backend.depth += 1;
+        if !graph_paper.points.is_empty() {
+            writeln!(
+                backend.out,
+                "\t\t\\addplot[only marks, mark=*, mark size={}, color=black] table {{",
+                Mm(P_RADIUS)
+            )?;
+            writeln!(backend.out, "\t\t\tx y")?;
+            for p in graph_paper.points.iter().filter(|p| p.x.is_finite() && p.y.is_finite()) {
+                writeln!(backend.out, "\t\t\t{} {}", p.x, p.y)?;
+            }
+            writeln!(backend.out, "\t\t}};")?;
+        }
+        // 軸の外へはみ出す部分はpgfplotsが切り抜く
+        backend.frame = Some(frame);
+        for series in &self.series {
+            backend.draw_all(&series.get_plot(graph_paper, self.x_scale.as_ref(), self.y_scale.as_ref()))?;
+        }
+        backend.frame = None;
+        backend.depth -= 1;
+        writeln!(backend.out, "\t\\end{{axis}}")?;
+        Ok(backend.finish()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph_paper::{GraphPaper, XLinearScale, YLogScale};
+    use crate::series::{FillBase, FillBetween};
+    use super::*;
+
+    #[test]
+    fn unmatched_end_group_is_an_error() {
+        let mut backend = TikzBackend::new(Vec::new()).unwrap();
+        backend.begin_group(None, None).unwrap();
+        backend.end_group().unwrap();
+        assert!(backend.end_group().is_err());
+    }
+
+    #[test]
+    fn pgfplots_series_use_axis_values() {
+        let graph = Graph {
+            graph_paper: GraphPaper {
+                name: String::new(),
+                margin: 100_f32,
+                size: Vec2::vec2(1100_f32, 1100_f32),
+                points: Vec::new(),
+                stroke_width: 1_f32,
+                great_split_length: 20_f32,
+                short_split_length: 10_f32
+            },
+            x_scale: Box::new(XLinearScale { h_great_split: 5, h_short_split: 2, max_value: 10_f32, reversed: false }),
+            y_scale: Box::new(YLogScale { base: 10_f32, from: 0, to: 2, reversed: false }),
+            series: vec![Box::new(FillBetween {
+                curve: vec![Vec2::vec2(0_f32, 10_f32), Vec2::vec2(5_f32, 100_f32)],
+                base: FillBase::Baseline(1_f32),
+                fill: "red".to_string(),
+                opacity: 0.5,
+                hatch: None
+            })]
+        };
+        let tex = String::from_utf8(graph.write_pgfplots(Vec::new()).unwrap()).unwrap();
+        assert!(!tex.contains("axis description cs"), "{}", tex);
+        assert!(tex.contains(
+            "\\addplot[forget plot, mark=none, draw=none, fill={rgb,255:red,255;green,0;blue,0}, fill opacity=0.5] \
+            coordinates {(0,10) (5,100) (5,1) (0,1) (0,10)};"
+        ), "{}", tex);
+    }
+}